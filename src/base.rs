@@ -2,7 +2,32 @@ use ctor::ctor;
 use winapi::um::consoleapi;
 use std::{error::Error, thread, time::Duration};
 
-use crate::outputs::{csdumper, methoddumper};
+use crate::{il2cpp::api, model::{game::Game, usage::MetadataUsage}, outputs::{assemblydumper, csdumper::{self, CsLayout}, graphdumper::{self, GraphFilter, GraphFormat}, htmldumper, icalldumper, methoddumper::{self, MethodsFormat}, scriptdumper, sink::Compression, sqldumper, stringdumper, typedumper::{self, TypesFormat}, usagedumper, xrefdumper}};
+
+// How methods.json is keyed, by name only, by full signature or as a list of entries with all details
+const METHODS_FORMAT: MethodsFormat = MethodsFormat::Names;
+
+// Whether the C# dump is a single dump.cs or a dump folder with a file per namespace or per class
+const CS_LAYOUT: CsLayout = CsLayout::SingleFile;
+
+// Whether types are saved as a single JSON document or as JSON Lines with one type per line
+const TYPES_FORMAT: TypesFormat = TypesFormat::Json;
+
+// Whether the type hierarchy is saved for Graphviz or as GraphML
+const GRAPH_FORMAT: GraphFormat = GraphFormat::Dot;
+
+// Limits the hierarchy to a namespace or to the types deriving from a root type, e.g. namespace: Some("RPG.Client")
+const GRAPH_FILTER: GraphFilter = GraphFilter { namespace: None, root: None, depth: None };
+
+// Also saves the current values of static fields, only useful when the game is in the state you're interested in
+const SNAPSHOT_STATIC_FIELDS: bool = false;
+
+// Sorts images, types and members by name instead of keeping the order the runtime has them in, and leaves out
+// heap addresses, so two dumps of the same build are identical and dumps of different versions diff cleanly
+const SORTED_OUTPUT: bool = false;
+
+// Whether text outputs are gzipped, the SQLite database, DummyDll assemblies and HTML site are always saved as is
+const COMPRESSION: Compression = Compression::None;
 
 fn init() -> Result<(), Box<dyn Error>> {
   thread::sleep(Duration::from_secs(10));
@@ -10,13 +35,55 @@ fn init() -> Result<(), Box<dyn Error>> {
   unsafe { consoleapi::AllocConsole() };
   println!("honkai-dumper");
 
+  // Outputs call into managed code through runtime_invoke, which needs this thread to be known to the runtime,
+  // without the export the dump goes on and those calls fail on their own
+  let il2cpp = api::get_il2cpp_api()?;
+
+  if let Err(error) = il2cpp.domain_get().and_then(|domain| il2cpp.thread_attach(domain)) {
+    println!("not attached to the runtime: {}", error);
+  }
+
   println!("dumping");
 
+  // Collecting goes through every class and method, so it's done once and shared by all outputs
+  let game = Game::collect(il2cpp, SNAPSHOT_STATIC_FIELDS, SORTED_OUTPUT)?;
+  let usages = MetadataUsage::collect_all(il2cpp, &game.instances)?;
+
   // Dumps only the method offsets
-  methoddumper::dump()?;
+  methoddumper::dump(&game, METHODS_FORMAT, COMPRESSION)?;
 
   // Dumsp all classes, fields and methods
-  csdumper::dump()?;
+  csdumper::dump(&game, CS_LAYOUT, COMPRESSION)?;
+
+  // Dumps all types with their fields and methods to JSON
+  typedumper::dump(&game, TYPES_FORMAT, COMPRESSION)?;
+
+  // Dumps a browsable site with a page per type
+  htmldumper::dump(&game)?;
+
+  // Dumps the inheritance and interface graph
+  graphdumper::dump(&game, GRAPH_FORMAT, &GRAPH_FILTER, COMPRESSION)?;
+
+  // Dumps where every type is used by fields, methods, subclasses and implementers
+  xrefdumper::dump(&game, COMPRESSION)?;
+
+  // Dumps all types with their fields, methods and parameters to a SQLite database
+  sqldumper::dump(&game)?;
+
+  // Dumps metadata-only assemblies that can be opened in dnSpy or ILSpy
+  assemblydumper::dump(&game)?;
+
+  // Dumps the native functions behind internal calls
+  icalldumper::dump(&game, COMPRESSION)?;
+
+  // Dumps the string literals code references
+  stringdumper::dump(&usages, COMPRESSION)?;
+
+  // Dumps the classes, methods, fields and strings code references through metadata usage slots
+  usagedumper::dump(&usages, COMPRESSION)?;
+
+  // Dumps names for methods and data references that disassembler scripts can apply
+  scriptdumper::dump(&game, &usages, SORTED_OUTPUT, COMPRESSION)?;
 
   println!("done");
 
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+// Unsigned integer in the compressed form signatures and blob lengths use, ECMA-335 II.23.2
+pub fn compress(value: u32, output: &mut Vec<u8>) {
+  if value < 0x80 {
+    output.push(value as u8);
+  }
+  else if value < 0x4000 {
+    output.extend_from_slice(&(value as u16 | 0x8000).to_be_bytes());
+  }
+  else {
+    output.extend_from_slice(&(value | 0xc0000000).to_be_bytes());
+  }
+}
+
+// #Strings, null terminated UTF-8 where offset 0 is the empty string
+#[derive(Default)]
+pub struct StringHeap {
+  data: Vec<u8>,
+  offsets: HashMap<String, u32>
+}
+
+impl StringHeap {
+  pub fn add(&mut self, value: &str) -> u32 {
+    if value.is_empty() {
+      return 0;
+    }
+
+    if let Some(&offset) = self.offsets.get(value) {
+      return offset;
+    }
+
+    if self.data.is_empty() {
+      self.data.push(0);
+    }
+
+    let offset = self.data.len() as u32;
+
+    self.data.extend_from_slice(value.as_bytes());
+    self.data.push(0);
+    self.offsets.insert(value.to_string(), offset);
+
+    offset
+  }
+
+  pub fn bytes(&self) -> Vec<u8> {
+    if self.data.is_empty() { vec![0] } else { self.data.clone() }
+  }
+
+  pub fn is_large(&self) -> bool {
+    self.data.len() >= 0x10000
+  }
+}
+
+// #Blob, every entry prefixed with its compressed length, offset 0 is the empty blob
+#[derive(Default)]
+pub struct BlobHeap {
+  data: Vec<u8>,
+  offsets: HashMap<Vec<u8>, u32>
+}
+
+impl BlobHeap {
+  pub fn add(&mut self, value: &[u8]) -> u32 {
+    if value.is_empty() {
+      return 0;
+    }
+
+    if let Some(&offset) = self.offsets.get(value) {
+      return offset;
+    }
+
+    if self.data.is_empty() {
+      self.data.push(0);
+    }
+
+    let offset = self.data.len() as u32;
+
+    compress(value.len() as u32, &mut self.data);
+    self.data.extend_from_slice(value);
+    self.offsets.insert(value.to_vec(), offset);
+
+    offset
+  }
+
+  pub fn bytes(&self) -> Vec<u8> {
+    if self.data.is_empty() { vec![0] } else { self.data.clone() }
+  }
+
+  pub fn is_large(&self) -> bool {
+    self.data.len() >= 0x10000
+  }
+}
+
+// #GUID, indexed from 1 in units of 16 bytes
+#[derive(Default)]
+pub struct GuidHeap {
+  data: Vec<u8>
+}
+
+impl GuidHeap {
+  pub fn add(&mut self, guid: [u8; 16]) -> u32 {
+    self.data.extend_from_slice(&guid);
+    (self.data.len() / 16) as u32
+  }
+
+  pub fn bytes(&self) -> Vec<u8> {
+    self.data.clone()
+  }
+
+  pub fn is_large(&self) -> bool {
+    self.data.len() / 16 >= 0x10000
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn compressed(value: u32) -> Vec<u8> {
+    let mut output = vec![];
+    compress(value, &mut output);
+    output
+  }
+
+  // The examples from ECMA-335 II.23.2
+  #[test]
+  fn compress_uses_the_shortest_form() {
+    assert_eq!(compressed(0x03), [0x03]);
+    assert_eq!(compressed(0x7f), [0x7f]);
+    assert_eq!(compressed(0x80), [0x80, 0x80]);
+    assert_eq!(compressed(0x2e57), [0xae, 0x57]);
+    assert_eq!(compressed(0x3fff), [0xbf, 0xff]);
+    assert_eq!(compressed(0x4000), [0xc0, 0x00, 0x40, 0x00]);
+    assert_eq!(compressed(0x1fffffff), [0xdf, 0xff, 0xff, 0xff]);
+  }
+
+  #[test]
+  fn strings_are_deduplicated_after_the_empty_string() {
+    let mut strings = StringHeap::default();
+
+    assert_eq!(strings.bytes(), [0]);
+    assert_eq!(strings.add(""), 0);
+    assert_eq!(strings.add("a"), 1);
+    assert_eq!(strings.add("bc"), 3);
+    assert_eq!(strings.add("a"), 1);
+    assert_eq!(strings.bytes(), b"\0a\0bc\0");
+  }
+
+  #[test]
+  fn blobs_are_prefixed_with_their_length() {
+    let mut blobs = BlobHeap::default();
+
+    assert_eq!(blobs.add(&[]), 0);
+    assert_eq!(blobs.add(&[1, 2]), 1);
+    assert_eq!(blobs.add(&[3]), 4);
+    assert_eq!(blobs.add(&[1, 2]), 1);
+    assert_eq!(blobs.bytes(), [0, 2, 1, 2, 1, 3]);
+
+    let long = vec![7; 0x80];
+    let offset = blobs.add(&long) as usize;
+    assert_eq!(blobs.bytes()[offset..offset + 2], [0x80, 0x80]);
+  }
+
+  #[test]
+  fn guids_are_indexed_from_one() {
+    let mut guids = GuidHeap::default();
+
+    assert_eq!(guids.add([1; 16]), 1);
+    assert_eq!(guids.add([2; 16]), 2);
+    assert_eq!(guids.bytes().len(), 32);
+  }
+
+  #[test]
+  fn heaps_become_large_at_64k() {
+    let mut strings = StringHeap::default();
+    strings.add(&"a".repeat(0xfffd));
+    assert!(!strings.is_large());
+
+    strings.add("b");
+    assert!(strings.is_large());
+  }
+}
@@ -0,0 +1,135 @@
+use super::{heaps::{BlobHeap, GuidHeap, StringHeap}, tables::{HeapSizes, Tables}};
+
+const VERSION: &[u8] = b"v4.0.30319";
+
+#[derive(Default)]
+pub struct Metadata {
+  pub strings: StringHeap,
+  pub blobs: BlobHeap,
+  pub guids: GuidHeap,
+  pub tables: Tables
+}
+
+fn pad(data: &mut Vec<u8>) {
+  while !data.len().is_multiple_of(4) {
+    data.push(0);
+  }
+}
+
+impl Metadata {
+  // Metadata root followed by its streams, ECMA-335 II.24.2.1
+  pub fn write(&self) -> Vec<u8> {
+    let sizes = HeapSizes {
+      large_strings: self.strings.is_large(),
+      large_guids: self.guids.is_large(),
+      large_blobs: self.blobs.is_large()
+    };
+
+    // #US is required to be present even though nothing uses string literals
+    let mut streams: Vec<(&str, Vec<u8>)> = vec![
+      ("#~", self.tables.write(&sizes)),
+      ("#Strings", self.strings.bytes()),
+      ("#US", vec![0]),
+      ("#GUID", self.guids.bytes()),
+      ("#Blob", self.blobs.bytes())
+    ];
+
+    for (_, data) in &mut streams {
+      pad(data);
+    }
+
+    let mut version = VERSION.to_vec();
+    version.push(0);
+    pad(&mut version);
+
+    let header_size = 16 + version.len() + 4 + streams.iter().map(|(name, _)| 8 + (name.len() + 4) / 4 * 4).sum::<usize>();
+
+    let mut output = vec![];
+
+    output.extend_from_slice(&0x424a5342u32.to_le_bytes());
+    output.extend_from_slice(&1u16.to_le_bytes());
+    output.extend_from_slice(&1u16.to_le_bytes());
+    output.extend_from_slice(&0u32.to_le_bytes());
+    output.extend_from_slice(&(version.len() as u32).to_le_bytes());
+    output.extend_from_slice(&version);
+    output.extend_from_slice(&0u16.to_le_bytes());
+    output.extend_from_slice(&(streams.len() as u16).to_le_bytes());
+
+    let mut offset = header_size;
+
+    for (name, data) in &streams {
+      output.extend_from_slice(&(offset as u32).to_le_bytes());
+      output.extend_from_slice(&(data.len() as u32).to_le_bytes());
+      output.extend_from_slice(name.as_bytes());
+      output.push(0);
+      pad(&mut output);
+
+      offset += data.len();
+    }
+
+    for (_, data) in &streams {
+      output.extend_from_slice(data);
+    }
+
+    output
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::emit::tables::{Column, MODULE};
+
+  fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+  }
+
+  // Reads the stream headers back and returns every stream by name
+  fn streams(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+    assert_eq!(u32_at(data, 0), 0x424a5342);
+
+    let version_length = u32_at(data, 12) as usize;
+    let count_offset = 16 + version_length + 2;
+    let count = u16::from_le_bytes([data[count_offset], data[count_offset + 1]]);
+
+    let mut offset = count_offset + 2;
+    let mut streams = vec![];
+
+    for _ in 0..count {
+      let start = u32_at(data, offset) as usize;
+      let size = u32_at(data, offset + 4) as usize;
+      let name_end = data[offset + 8..].iter().position(|&byte| byte == 0).unwrap();
+      let name = String::from_utf8(data[offset + 8..offset + 8 + name_end].to_vec()).unwrap();
+
+      streams.push((name, data[start..start + size].to_vec()));
+      offset += 8 + (name_end + 4) / 4 * 4;
+    }
+
+    streams
+  }
+
+  #[test]
+  fn streams_round_trip() {
+    let mut metadata = Metadata::default();
+
+    let name = metadata.strings.add("Game.dll");
+    let blob = metadata.blobs.add(&[0x06, 0x08]);
+    let guid = metadata.guids.add([0xab; 16]);
+    metadata.tables.push(MODULE, vec![Column::U16(0), Column::String(name), Column::Guid(guid), Column::Guid(0), Column::Guid(0)]);
+
+    let data = metadata.write();
+    let streams = streams(&data);
+
+    let names: Vec<&str> = streams.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["#~", "#Strings", "#US", "#GUID", "#Blob"]);
+
+    for (_, stream) in &streams {
+      assert!(stream.len().is_multiple_of(4));
+    }
+
+    assert_eq!(streams[1].1[name as usize..name as usize + 9], *b"Game.dll\0");
+    assert_eq!(streams[3].1[..16], [0xab; 16]);
+    assert_eq!(streams[4].1[blob as usize..blob as usize + 3], [2, 0x06, 0x08]);
+    assert_eq!(streams[0].1[..4], [0, 0, 0, 0]);
+  }
+}
@@ -0,0 +1,4 @@
+pub mod heaps;
+pub mod metadata;
+pub mod pe;
+pub mod tables;
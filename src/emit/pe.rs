@@ -0,0 +1,117 @@
+// A PE32 image with a single .text section holding the CLI header, method bodies and metadata, ECMA-335 II.25
+const FILE_ALIGNMENT: usize = 0x200;
+const SECTION_ALIGNMENT: usize = 0x2000;
+const TEXT_RVA: u32 = 0x2000;
+const CLI_HEADER_SIZE: u32 = 0x48;
+
+// Method bodies start right after the CLI header
+pub const BODIES_RVA: u32 = TEXT_RVA + CLI_HEADER_SIZE;
+
+fn align(value: usize, alignment: usize) -> usize {
+  value.div_ceil(alignment) * alignment
+}
+
+fn push_u16(output: &mut Vec<u8>, value: u16) {
+  output.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(output: &mut Vec<u8>, value: u32) {
+  output.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn write(bodies: &[u8], metadata: &[u8]) -> Vec<u8> {
+  let metadata_rva = BODIES_RVA as usize + align(bodies.len(), 4);
+  let text_size = metadata_rva - TEXT_RVA as usize + metadata.len();
+  let raw_size = align(text_size, FILE_ALIGNMENT);
+
+  let mut output = vec![0u8; 0x80];
+
+  // DOS header, only e_magic and e_lfanew matter
+  output[0..2].copy_from_slice(b"MZ");
+  output[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+
+  output.extend_from_slice(b"PE\0\0");
+
+  // COFF header: i386, one section, executable DLL
+  push_u16(&mut output, 0x14c);
+  push_u16(&mut output, 1);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 0);
+  push_u16(&mut output, 0xe0);
+  push_u16(&mut output, 0x2102);
+
+  // Optional header
+  push_u16(&mut output, 0x10b);
+  output.extend_from_slice(&[8, 0]);
+  push_u32(&mut output, raw_size as u32);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, TEXT_RVA);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 0x10000000);
+  push_u32(&mut output, SECTION_ALIGNMENT as u32);
+  push_u32(&mut output, FILE_ALIGNMENT as u32);
+  push_u16(&mut output, 4);
+  push_u16(&mut output, 0);
+  push_u16(&mut output, 0);
+  push_u16(&mut output, 0);
+  push_u16(&mut output, 4);
+  push_u16(&mut output, 0);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, (TEXT_RVA as usize + align(text_size, SECTION_ALIGNMENT)) as u32);
+  push_u32(&mut output, FILE_ALIGNMENT as u32);
+  push_u32(&mut output, 0);
+  push_u16(&mut output, 3);
+  push_u16(&mut output, 0x8540);
+  push_u32(&mut output, 0x100000);
+  push_u32(&mut output, 0x1000);
+  push_u32(&mut output, 0x100000);
+  push_u32(&mut output, 0x1000);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 16);
+
+  // Data directories, only the CLI header is set
+  for directory in 0..16 {
+    if directory == 14 {
+      push_u32(&mut output, TEXT_RVA);
+      push_u32(&mut output, CLI_HEADER_SIZE);
+    }
+    else {
+      push_u32(&mut output, 0);
+      push_u32(&mut output, 0);
+    }
+  }
+
+  // .text section header
+  output.extend_from_slice(b".text\0\0\0");
+  push_u32(&mut output, text_size as u32);
+  push_u32(&mut output, TEXT_RVA);
+  push_u32(&mut output, raw_size as u32);
+  push_u32(&mut output, FILE_ALIGNMENT as u32);
+  push_u32(&mut output, 0);
+  push_u32(&mut output, 0);
+  push_u16(&mut output, 0);
+  push_u16(&mut output, 0);
+  push_u32(&mut output, 0x60000020);
+
+  output.resize(FILE_ALIGNMENT, 0);
+
+  // CLI header: runtime 2.5, metadata, IL only
+  push_u32(&mut output, CLI_HEADER_SIZE);
+  push_u16(&mut output, 2);
+  push_u16(&mut output, 5);
+  push_u32(&mut output, metadata_rva as u32);
+  push_u32(&mut output, metadata.len() as u32);
+  push_u32(&mut output, 1);
+  output.resize(FILE_ALIGNMENT + CLI_HEADER_SIZE as usize, 0);
+
+  output.extend_from_slice(bodies);
+  output.resize(FILE_ALIGNMENT + metadata_rva - TEXT_RVA as usize, 0);
+
+  output.extend_from_slice(metadata);
+  output.resize(FILE_ALIGNMENT + raw_size, 0);
+
+  output
+}
@@ -0,0 +1,288 @@
+// Table numbers, ECMA-335 II.22
+pub const MODULE: usize = 0x00;
+pub const TYPE_REF: usize = 0x01;
+pub const TYPE_DEF: usize = 0x02;
+pub const FIELD: usize = 0x04;
+pub const METHOD_DEF: usize = 0x06;
+pub const PARAM: usize = 0x08;
+pub const INTERFACE_IMPL: usize = 0x09;
+pub const MEMBER_REF: usize = 0x0a;
+pub const CONSTANT: usize = 0x0b;
+pub const CUSTOM_ATTRIBUTE: usize = 0x0c;
+pub const EVENT_MAP: usize = 0x12;
+pub const EVENT: usize = 0x14;
+pub const PROPERTY_MAP: usize = 0x15;
+pub const PROPERTY: usize = 0x17;
+pub const METHOD_SEMANTICS: usize = 0x18;
+pub const MODULE_REF: usize = 0x1a;
+pub const TYPE_SPEC: usize = 0x1b;
+pub const ASSEMBLY: usize = 0x20;
+pub const ASSEMBLY_REF: usize = 0x23;
+pub const NESTED_CLASS: usize = 0x29;
+pub const GENERIC_PARAM: usize = 0x2a;
+pub const GENERIC_PARAM_CONSTRAINT: usize = 0x2c;
+
+// Tables that are referenced by coded indices but never written
+const DECL_SECURITY: usize = 0x0e;
+const STAND_ALONE_SIG: usize = 0x11;
+const FILE: usize = 0x26;
+const EXPORTED_TYPE: usize = 0x27;
+const MANIFEST_RESOURCE: usize = 0x28;
+const METHOD_SPEC: usize = 0x2b;
+
+// Placeholder for tags that aren't assigned to any table
+const UNUSED: usize = usize::MAX;
+
+// Tables a coded index can point into, the position in the list is the tag, ECMA-335 II.24.2.6
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Coded {
+  TypeDefOrRef,
+  HasConstant,
+  HasCustomAttribute,
+  MemberRefParent,
+  HasSemantics,
+  CustomAttributeType,
+  ResolutionScope,
+  TypeOrMethodDef
+}
+
+impl Coded {
+  fn tables(&self) -> &'static [usize] {
+    match self {
+      Coded::TypeDefOrRef => &[TYPE_DEF, TYPE_REF, TYPE_SPEC],
+      Coded::HasConstant => &[FIELD, PARAM, PROPERTY],
+      Coded::HasCustomAttribute => &[
+        METHOD_DEF, FIELD, TYPE_REF, TYPE_DEF, PARAM, INTERFACE_IMPL, MEMBER_REF, MODULE, DECL_SECURITY, PROPERTY, EVENT,
+        STAND_ALONE_SIG, MODULE_REF, TYPE_SPEC, ASSEMBLY, ASSEMBLY_REF, FILE, EXPORTED_TYPE, MANIFEST_RESOURCE, GENERIC_PARAM,
+        GENERIC_PARAM_CONSTRAINT, METHOD_SPEC
+      ],
+      Coded::MemberRefParent => &[TYPE_DEF, TYPE_REF, MODULE_REF, METHOD_DEF, TYPE_SPEC],
+      Coded::HasSemantics => &[EVENT, PROPERTY],
+      Coded::CustomAttributeType => &[UNUSED, UNUSED, METHOD_DEF, MEMBER_REF, UNUSED],
+      Coded::ResolutionScope => &[MODULE, MODULE_REF, ASSEMBLY_REF, TYPE_REF],
+      Coded::TypeOrMethodDef => &[TYPE_DEF, METHOD_DEF]
+    }
+  }
+
+  fn bits(&self) -> u32 {
+    usize::BITS - (self.tables().len() - 1).leading_zeros()
+  }
+
+  // Row in one of the tables, encoded with the table's tag
+  pub fn encode(&self, table: usize, row: u32) -> u32 {
+    let tag = self.tables().iter().position(|&candidate| candidate == table).expect("table can't be used in this coded index");
+    (row << self.bits()) | tag as u32
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+  U16(u16),
+  U32(u32),
+  String(u32),
+  Guid(u32),
+  Blob(u32),
+  Index(usize, u32),
+  Coded(Coded, u32)
+}
+
+pub type Row = Vec<Column>;
+
+pub struct HeapSizes {
+  pub large_strings: bool,
+  pub large_guids: bool,
+  pub large_blobs: bool
+}
+
+// The #~ stream, rows are 1-based and appended in the order they're defined
+pub struct Tables {
+  rows: Vec<Vec<Row>>
+}
+
+impl Default for Tables {
+  fn default() -> Tables {
+    Tables { rows: vec![vec![]; 64] }
+  }
+}
+
+impl Tables {
+  pub fn push(&mut self, table: usize, row: Row) -> u32 {
+    self.rows[table].push(row);
+    self.rows[table].len() as u32
+  }
+
+  // Index of the row that would be added next, for the member lists of TypeDef, PropertyMap and EventMap
+  pub fn next_row(&self, table: usize) -> u32 {
+    self.rows[table].len() as u32 + 1
+  }
+
+  // Tables that have to be sorted by their key columns can be built in any order and sorted once at the end
+  pub fn sort(&mut self, table: usize, key_columns: &[usize]) {
+    self.rows[table].sort_by(|a, b| {
+      key_columns.iter()
+        .map(|&column| sort_key(&a[column]).cmp(&sort_key(&b[column])))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+    });
+  }
+
+  fn is_large(&self, table: usize) -> bool {
+    table != UNUSED && self.rows[table].len() >= 0x10000
+  }
+
+  fn coded_is_large(&self, coded: Coded) -> bool {
+    let limit = 1usize << (16 - coded.bits());
+    coded.tables().iter().any(|&table| table != UNUSED && self.rows[table].len() >= limit)
+  }
+
+  fn write_column(&self, column: &Column, sizes: &HeapSizes, output: &mut Vec<u8>) {
+    let (value, large) = match *column {
+      Column::U16(value) => (value as u32, false),
+      Column::U32(value) => (value, true),
+      Column::String(value) => (value, sizes.large_strings),
+      Column::Guid(value) => (value, sizes.large_guids),
+      Column::Blob(value) => (value, sizes.large_blobs),
+      Column::Index(table, value) => (value, self.is_large(table)),
+      Column::Coded(coded, value) => (value, self.coded_is_large(coded))
+    };
+
+    if large {
+      output.extend_from_slice(&value.to_le_bytes());
+    }
+    else {
+      output.extend_from_slice(&(value as u16).to_le_bytes());
+    }
+  }
+
+  pub fn write(&self, sizes: &HeapSizes) -> Vec<u8> {
+    let mut output = vec![];
+
+    let present: Vec<usize> = (0..self.rows.len()).filter(|&table| !self.rows[table].is_empty()).collect();
+    let valid = present.iter().fold(0u64, |mask, &table| mask | (1 << table));
+    let sorted = [INTERFACE_IMPL, CONSTANT, CUSTOM_ATTRIBUTE, METHOD_SEMANTICS, NESTED_CLASS, GENERIC_PARAM, GENERIC_PARAM_CONSTRAINT].iter().fold(0u64, |mask, &table| mask | (1 << table));
+
+    let heap_sizes = sizes.large_strings as u8 | (sizes.large_guids as u8) << 1 | (sizes.large_blobs as u8) << 2;
+
+    output.extend_from_slice(&0u32.to_le_bytes());
+    output.extend_from_slice(&[2, 0, heap_sizes, 1]);
+    output.extend_from_slice(&valid.to_le_bytes());
+    output.extend_from_slice(&sorted.to_le_bytes());
+
+    for &table in &present {
+      output.extend_from_slice(&(self.rows[table].len() as u32).to_le_bytes());
+    }
+
+    for &table in &present {
+      for row in &self.rows[table] {
+        for column in row {
+          self.write_column(column, sizes, &mut output);
+        }
+      }
+    }
+
+    output
+  }
+}
+
+fn sort_key(column: &Column) -> u32 {
+  match *column {
+    Column::U16(value) => value as u32,
+    Column::U32(value) | Column::String(value) | Column::Guid(value) | Column::Blob(value) | Column::Index(_, value) | Column::Coded(_, value) => value
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SMALL_HEAPS: HeapSizes = HeapSizes { large_strings: false, large_guids: false, large_blobs: false };
+
+  fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+  }
+
+  fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+  }
+
+  #[test]
+  fn coded_indices_have_enough_bits_for_their_tags() {
+    assert_eq!(Coded::TypeDefOrRef.bits(), 2);
+    assert_eq!(Coded::HasConstant.bits(), 2);
+    assert_eq!(Coded::HasCustomAttribute.bits(), 5);
+    assert_eq!(Coded::MemberRefParent.bits(), 3);
+    assert_eq!(Coded::HasSemantics.bits(), 1);
+    assert_eq!(Coded::CustomAttributeType.bits(), 3);
+    assert_eq!(Coded::ResolutionScope.bits(), 2);
+    assert_eq!(Coded::TypeOrMethodDef.bits(), 1);
+  }
+
+  #[test]
+  fn coded_indices_put_the_tag_in_the_low_bits() {
+    assert_eq!(Coded::TypeDefOrRef.encode(TYPE_DEF, 3), 3 << 2);
+    assert_eq!(Coded::TypeDefOrRef.encode(TYPE_SPEC, 1), (1 << 2) | 2);
+    assert_eq!(Coded::HasCustomAttribute.encode(PARAM, 2), (2 << 5) | 4);
+    assert_eq!(Coded::CustomAttributeType.encode(MEMBER_REF, 5), (5 << 3) | 3);
+    assert_eq!(Coded::HasSemantics.encode(PROPERTY, 7), (7 << 1) | 1);
+  }
+
+  #[test]
+  fn rows_are_numbered_from_one() {
+    let mut tables = Tables::default();
+
+    assert_eq!(tables.next_row(FIELD), 1);
+    assert_eq!(tables.push(FIELD, vec![Column::U16(0), Column::String(0), Column::Blob(0)]), 1);
+    assert_eq!(tables.next_row(FIELD), 2);
+  }
+
+  #[test]
+  fn stream_has_header_row_counts_and_rows() {
+    let mut tables = Tables::default();
+
+    tables.push(MODULE, vec![Column::U16(0), Column::String(1), Column::Guid(1), Column::Guid(0), Column::Guid(0)]);
+    tables.push(TYPE_REF, vec![Column::Coded(Coded::ResolutionScope, Coded::ResolutionScope.encode(ASSEMBLY_REF, 1)), Column::String(3), Column::String(5)]);
+    tables.push(TYPE_REF, vec![Column::Coded(Coded::ResolutionScope, 0), Column::String(7), Column::String(0)]);
+
+    let data = tables.write(&SMALL_HEAPS);
+
+    // Reserved, version 2.0, heap sizes and the reserved 1
+    assert_eq!(data[..8], [0, 0, 0, 0, 2, 0, 0, 1]);
+
+    let valid = u64::from_le_bytes(data[8..16].try_into().unwrap());
+    assert_eq!(valid, (1 << MODULE) | (1 << TYPE_REF));
+
+    assert_eq!(u32_at(&data, 24), 1);
+    assert_eq!(u32_at(&data, 28), 2);
+
+    // Module has five 2 byte columns and TypeRef three, all indices fit in 2 bytes
+    let rows = 32;
+    assert_eq!(data.len(), rows + 10 + 2 * 6);
+
+    assert_eq!(u16_at(&data, rows + 2), 1);
+    assert_eq!(u16_at(&data, rows + 10) as u32, Coded::ResolutionScope.encode(ASSEMBLY_REF, 1));
+    assert_eq!(u16_at(&data, rows + 12), 3);
+    assert_eq!(u16_at(&data, rows + 16 + 2), 7);
+  }
+
+  #[test]
+  fn coded_indices_grow_with_the_tables_they_point_into() {
+    let mut tables = Tables::default();
+    tables.push(INTERFACE_IMPL, vec![Column::Index(TYPE_DEF, 1), Column::Coded(Coded::TypeDefOrRef, 0)]);
+
+    let small = tables.write(&SMALL_HEAPS).len();
+
+    // Two tag bits leave 14 bits for the row, TypeDef indices stay small
+    for _ in 0..0x4000 {
+      tables.push(TYPE_REF, vec![Column::Coded(Coded::ResolutionScope, 0), Column::String(0), Column::String(0)]);
+    }
+
+    let data = tables.write(&SMALL_HEAPS);
+    // ResolutionScope can point at a TypeRef too, so the rows themselves grow as well
+    let type_ref_rows = 0x4000 * 8;
+
+    assert!(!tables.is_large(TYPE_DEF));
+    assert!(tables.coded_is_large(Coded::TypeDefOrRef));
+    assert!(tables.coded_is_large(Coded::ResolutionScope));
+    assert_eq!(data.len(), small + 4 + type_ref_rows + 2);
+  }
+}
@@ -1,7 +1,7 @@
-use std::{error::Error, ffi::{c_void, CStr}, path::PathBuf, str::Utf8Error};
+use std::{cell::OnceCell, error::Error, ffi::{c_void, CStr, CString, NulError}, path::PathBuf, ptr::null, str::Utf8Error};
 use thiserror::Error;
 
-use super::{functions::Il2CppFunctions, module::{Module, ModuleError}, types::*};
+use super::{functions::Il2CppFunctions, metadata, module::{FunctionPtr, Module, ModuleError}, types::*};
 
 macro_rules! get_function_safe {
   ($self:ident, $name:ident) => {{
@@ -16,7 +16,7 @@ macro_rules! cstr_to_string {
   }};
 }
 
-const PRIMITIVE_TYPES: &[(&str, &str)] = &[
+pub const PRIMITIVE_TYPES: &[(&str, &str)] = &[
   ("System.Void", "void"),
   ("System.Boolean", "bool"),
   ("System.Char", "char"),
@@ -33,8 +33,7 @@ const PRIMITIVE_TYPES: &[(&str, &str)] = &[
   ("System.String", "string"),
   ("System.IntPtr", "IntPtr"),
   ("System.UIntPtr", "UIntPtr"),
-  ("System.Object", "object"),
-  ("&", "")
+  ("System.Object", "object")
 ];
 
 #[derive(Debug, Error)]
@@ -43,6 +42,8 @@ pub enum Il2CppError {
   Module(#[from] ModuleError),
   #[error(transparent)]
   Utf8(#[from] Utf8Error),
+  #[error(transparent)]
+  Nul(#[from] NulError),
 
   #[error("file not found {0}")]
   FileNotFound(&'static str),
@@ -51,13 +52,18 @@ pub enum Il2CppError {
   #[error("root path not found")]
   RootNotFound,
   #[error("function returned null {0}")]
-  ReturnedNull(&'static str)
+  ReturnedNull(&'static str),
+  #[error("exception thrown while invoking {0}")]
+  Exception(String),
+  #[error("metadata registration not found")]
+  MetadataRegistrationNotFound
 }
 
 pub struct Il2CppApi {
   pub game_assembly: Module,
   pub unity_player: Module,
-  pub functions: Il2CppFunctions
+  pub functions: Il2CppFunctions,
+  metadata_registration: OnceCell<Option<*const Il2CppMetadataRegistration>>
 }
 
 impl Il2CppApi {
@@ -75,15 +81,61 @@ impl Il2CppApi {
 
     let game_assembly = Module::load(game_assembly_path)?;
     let unity_player = Module::load(unity_player_path)?;
-    let functions = Il2CppFunctions::new(unity_player.handle as usize);
+    let mut functions = Il2CppFunctions::new(unity_player.handle as usize);
+    functions.il2cpp_thread_attach = game_assembly.export("il2cpp_thread_attach").map(FunctionPtr::new);
 
     Ok(Il2CppApi {
       game_assembly,
       unity_player,
-      functions
+      functions,
+      metadata_registration: OnceCell::new()
     })
   }
 
+  // Looked up once since it means scanning the whole of GameAssembly
+  pub fn metadata_registration(&self) -> Result<&'static Il2CppMetadataRegistration, Il2CppError> {
+    let registration = match self.metadata_registration.get() {
+      Some(registration) => *registration,
+      None => {
+        let registration = metadata::find_metadata_registration(&self.game_assembly, self.type_definition_count()?);
+        *self.metadata_registration.get_or_init(|| registration)
+      }
+    };
+
+    // Only set once it passed the checks in find_metadata_registration
+    registration.map(|registration| unsafe { &*registration }).ok_or(Il2CppError::MetadataRegistrationNotFound)
+  }
+
+  fn type_definition_count(&self) -> Result<usize, Il2CppError> {
+    let domain = self.domain_get()?;
+
+    let assembly_count: usize = 0;
+    let assemblies = self.domain_get_assemblies(domain, &assembly_count)?;
+
+    let mut count = 0;
+
+    for i in 0..assembly_count {
+      let assembly = unsafe { *assemblies.add(i) };
+
+      if !assembly.is_null() {
+        count += self.image_get_class_count(self.assembly_get_image(assembly)?)?;
+      }
+    }
+
+    Ok(count)
+  }
+
+  pub fn array_new(&self, element_class: *const Il2CppClass, length: usize) -> Result<*const Il2CppArray, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_array_new);
+    let array = function(element_class, length);
+
+    if array.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_array_new"));
+    }
+
+    Ok(array)
+  }
+
   pub fn domain_get(&self) -> Result<*const Il2CppDomain, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_domain_get);
     let domain = function();
@@ -150,18 +202,43 @@ impl Il2CppApi {
     Ok(if result.is_null() { None } else { Some(result) })
   }
 
+  pub fn class_get_nested_types(&self, class: *const Il2CppClass, iter: *const *const c_void) -> Result<Option<*const Il2CppClass>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_nested_types);
+    let result = function(class, iter);
+    Ok(if result.is_null() { None } else { Some(result) })
+  }
+
   pub fn class_get_interfaces(&self, class: *const Il2CppClass, iter: *const *const c_void) -> Result<Option<*const Il2CppClass>, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_class_get_interfaces);
     let result = function(class, iter);
     Ok(if result.is_null() { None } else { Some(result) })
   }
 
+  pub fn class_get_properties(&self, class: *const Il2CppClass, iter: *const *const c_void) -> Result<Option<*const PropertyInfo>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_properties);
+    let result = function(class, iter);
+    Ok(if result.is_null() { None } else { Some(result) })
+  }
+
+  pub fn class_get_events(&self, class: *const Il2CppClass, iter: *const *const c_void) -> Result<Option<*const EventInfo>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_events);
+    let result = function(class, iter);
+    Ok(if result.is_null() { None } else { Some(result) })
+  }
+
   pub fn class_get_methods(&self, class: *const Il2CppClass, iter: *const *const c_void) -> Result<Option<*const MethodInfo>, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_class_get_methods);
     let result = function(class, iter);
     Ok(if result.is_null() { None } else { Some(result) })
   }
 
+  pub fn class_get_method_from_name(&self, class: *const Il2CppClass, name: &str, args_count: i32) -> Result<Option<*const MethodInfo>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_method_from_name);
+    let name_c = CString::new(name)?;
+    let method = function(class, name_c.as_ptr(), args_count);
+    Ok(if method.is_null() { None } else { Some(method) })
+  }
+
   pub fn class_get_name(&self, class: *const Il2CppClass) -> Result<String, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_class_get_name);
     let name_c = function(class);
@@ -195,6 +272,12 @@ impl Il2CppApi {
     Ok(parent)
   }
 
+  pub fn class_get_declaring_type(&self, class: *const Il2CppClass) -> Result<Option<*const Il2CppClass>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_declaring_type);
+    let declaring_type = function(class);
+    Ok(if declaring_type.is_null() { None } else { Some(declaring_type) })
+  }
+
   pub fn class_get_flags(&self, class: *const Il2CppClass) -> Result<i32, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_class_get_flags);
     Ok(function(class))
@@ -211,6 +294,17 @@ impl Il2CppApi {
     Ok(class)
   }
 
+  pub fn class_get_type(&self, class: *const Il2CppClass) -> Result<*const Il2CppType, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_type);
+    let class_type = function(class);
+
+    if class_type.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_class_get_type"));
+    }
+
+    Ok(class_type)
+  }
+
   pub fn class_is_enum(&self, class: *const Il2CppClass) -> Result<bool, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_class_is_enum);
     Ok(function(class))
@@ -221,6 +315,47 @@ impl Il2CppApi {
     Ok(function(class))
   }
 
+  pub fn class_get_image(&self, class: *const Il2CppClass) -> Result<*const Il2CppImage, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_image);
+    let image = function(class);
+
+    if image.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_class_get_image"));
+    }
+
+    Ok(image)
+  }
+
+  pub fn class_instance_size(&self, class: *const Il2CppClass) -> Result<i32, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_instance_size);
+    Ok(function(class))
+  }
+
+  // Returns the size of the class when stored inline together with its alignment
+  pub fn class_value_size(&self, class: *const Il2CppClass) -> Result<(i32, u32), Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_value_size);
+    let mut align = 0;
+    let size = function(class, &mut align);
+    Ok((size, align))
+  }
+
+  pub fn class_array_element_size(&self, class: *const Il2CppClass) -> Result<i32, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_array_element_size);
+    Ok(function(class))
+  }
+
+  pub fn class_get_data_size(&self, class: *const Il2CppClass) -> Result<i32, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_data_size);
+    Ok(function(class))
+  }
+
+  // Null until the class is initialized or when it has no static fields
+  pub fn class_get_static_field_data(&self, class: *const Il2CppClass) -> Result<Option<*const c_void>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_class_get_static_field_data);
+    let data = function(class);
+    Ok(if data.is_null() { None } else { Some(data) })
+  }
+
   pub fn field_get_flags(&self, field: *const FieldInfo) -> Result<i32, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_field_get_flags);
     Ok(function(field))
@@ -253,6 +388,12 @@ impl Il2CppApi {
     Ok(field_type)
   }
 
+  pub fn field_static_get_value(&self, field: *const FieldInfo, value: *mut c_void) -> Result<(), Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_field_static_get_value);
+    function(field, value);
+    Ok(())
+  }
+
   pub fn method_get_return_type(&self, method: *const MethodInfo) -> Result<*const Il2CppType, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_method_get_return_type);
     let return_type = function(method);
@@ -275,6 +416,11 @@ impl Il2CppApi {
     Ok(cstr_to_string!(name_c))
   }
 
+  pub fn method_is_generic(&self, method: *const MethodInfo) -> Result<bool, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_method_is_generic);
+    Ok(function(method))
+  }
+
   pub fn method_get_param_count(&self, method: *const MethodInfo) -> Result<u32, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_method_get_param_count);
     Ok(function(method))
@@ -291,6 +437,105 @@ impl Il2CppApi {
     Ok(param)
   }
 
+  // Returns the method attributes together with the implementation attributes
+  pub fn method_get_flags(&self, method: *const MethodInfo) -> Result<(u32, u32), Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_method_get_flags);
+    let mut iflags = 0;
+    let flags = function(method, &mut iflags);
+    Ok((flags, iflags))
+  }
+
+  pub fn method_get_param_name(&self, method: *const MethodInfo, index: u32) -> Result<String, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_method_get_param_name);
+    let name_c = function(method, index);
+
+    if name_c.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_method_get_param_name"));
+    }
+
+    Ok(cstr_to_string!(name_c))
+  }
+
+  pub fn method_get_object(&self, method: *const MethodInfo) -> Result<*const Il2CppObject, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_method_get_object);
+    let object = function(method, null());
+
+    if object.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_method_get_object"));
+    }
+
+    Ok(object)
+  }
+
+  pub fn resolve_icall(&self, name: &str) -> Result<Option<*const c_void>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_resolve_icall);
+    let name_c = CString::new(name)?;
+    let pointer = function(name_c.as_ptr());
+    Ok(if pointer.is_null() { None } else { Some(pointer) })
+  }
+
+  pub fn runtime_invoke(&self, method: *const MethodInfo, object: *const c_void, params: *const *const c_void) -> Result<*const Il2CppObject, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_runtime_invoke);
+    let mut exception: *const Il2CppObject = null();
+    let result = function(method, object, params, &mut exception);
+
+    if !exception.is_null() {
+      return Err(Il2CppError::Exception(self.method_get_name(method)?));
+    }
+
+    Ok(result)
+  }
+
+  pub fn thread_attach(&self, domain: *const Il2CppDomain) -> Result<*const Il2CppThread, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_thread_attach);
+    let thread = function(domain);
+
+    if thread.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_thread_attach"));
+    }
+
+    Ok(thread)
+  }
+
+  pub fn property_get_flags(&self, property: *const PropertyInfo) -> Result<u32, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_property_get_flags);
+    Ok(function(property))
+  }
+
+  pub fn property_get_get_method(&self, property: *const PropertyInfo) -> Result<Option<*const MethodInfo>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_property_get_get_method);
+    let method = function(property);
+    Ok(if method.is_null() { None } else { Some(method) })
+  }
+
+  pub fn property_get_set_method(&self, property: *const PropertyInfo) -> Result<Option<*const MethodInfo>, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_property_get_set_method);
+    let method = function(property);
+    Ok(if method.is_null() { None } else { Some(method) })
+  }
+
+  pub fn property_get_name(&self, property: *const PropertyInfo) -> Result<String, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_property_get_name);
+    let name_c = function(property);
+
+    if name_c.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_property_get_name"));
+    }
+
+    Ok(cstr_to_string!(name_c))
+  }
+
+  pub fn type_get_object(&self, _type: *const Il2CppType) -> Result<*const Il2CppObject, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_type_get_object);
+    let object = function(_type);
+
+    if object.is_null() {
+      return Err(Il2CppError::ReturnedNull("il2cpp_type_get_object"));
+    }
+
+    Ok(object)
+  }
+
   pub fn type_get_name(&self, _type: *const Il2CppType) -> Result<String, Il2CppError> {
     let function = get_function_safe!(self, il2cpp_type_get_name);
     let name_c = function(_type);
@@ -299,13 +544,12 @@ impl Il2CppApi {
       return Err(Il2CppError::ReturnedNull("il2cpp_type_get_name"));
     }
 
-    let mut name = cstr_to_string!(name_c);
-
-    for &(k, v) in PRIMITIVE_TYPES {
-      name = name.replace(k, v);
-    }
+    Ok(cstr_to_string!(name_c))
+  }
 
-    Ok(name)
+  pub fn type_get_type(&self, type_: *const Il2CppType) -> Result<u8, Il2CppError> {
+    let function = get_function_safe!(self, il2cpp_type_get_type);
+    Ok(function(type_) as u8)
   }
 
   pub fn type_is_byref(&self, type_: *const Il2CppType) -> Result<bool, Il2CppError> {
@@ -127,4 +127,40 @@ pub const IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_SPECIAL_CONSTRAINT_MASK: i32 = 0x1C
 pub const ASSEMBLYREF_FULL_PUBLIC_KEY_FLAG: i32 = 0x00000001;
 pub const ASSEMBLYREF_RETARGETABLE_FLAG: i32 = 0x00000100;
 pub const ASSEMBLYREF_ENABLEJITCOMPILE_TRACKING_FLAG: i32 = 0x00008000;
-pub const ASSEMBLYREF_DISABLEJITCOMPILE_OPTIMIZER_FLAG: i32 = 0x00004000;
\ No newline at end of file
+pub const ASSEMBLYREF_DISABLEJITCOMPILE_OPTIMIZER_FLAG: i32 = 0x00004000;
+
+// il2cpp-class-internals.h
+
+pub const THREAD_STATIC_FIELD_OFFSET: usize = usize::MAX;
+
+// il2cpp-blob.h
+
+pub const IL2CPP_TYPE_END: u8 = 0x00;
+pub const IL2CPP_TYPE_VOID: u8 = 0x01;
+pub const IL2CPP_TYPE_BOOLEAN: u8 = 0x02;
+pub const IL2CPP_TYPE_CHAR: u8 = 0x03;
+pub const IL2CPP_TYPE_I1: u8 = 0x04;
+pub const IL2CPP_TYPE_U1: u8 = 0x05;
+pub const IL2CPP_TYPE_I2: u8 = 0x06;
+pub const IL2CPP_TYPE_U2: u8 = 0x07;
+pub const IL2CPP_TYPE_I4: u8 = 0x08;
+pub const IL2CPP_TYPE_U4: u8 = 0x09;
+pub const IL2CPP_TYPE_I8: u8 = 0x0a;
+pub const IL2CPP_TYPE_U8: u8 = 0x0b;
+pub const IL2CPP_TYPE_R4: u8 = 0x0c;
+pub const IL2CPP_TYPE_R8: u8 = 0x0d;
+pub const IL2CPP_TYPE_STRING: u8 = 0x0e;
+pub const IL2CPP_TYPE_PTR: u8 = 0x0f;
+pub const IL2CPP_TYPE_BYREF: u8 = 0x10;
+pub const IL2CPP_TYPE_VALUETYPE: u8 = 0x11;
+pub const IL2CPP_TYPE_CLASS: u8 = 0x12;
+pub const IL2CPP_TYPE_VAR: u8 = 0x13;
+pub const IL2CPP_TYPE_ARRAY: u8 = 0x14;
+pub const IL2CPP_TYPE_GENERICINST: u8 = 0x15;
+pub const IL2CPP_TYPE_TYPEDBYREF: u8 = 0x16;
+pub const IL2CPP_TYPE_I: u8 = 0x18;
+pub const IL2CPP_TYPE_U: u8 = 0x19;
+pub const IL2CPP_TYPE_FNPTR: u8 = 0x1b;
+pub const IL2CPP_TYPE_OBJECT: u8 = 0x1c;
+pub const IL2CPP_TYPE_SZARRAY: u8 = 0x1d;
+pub const IL2CPP_TYPE_MVAR: u8 = 0x1e;
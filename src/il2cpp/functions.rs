@@ -11,18 +11,32 @@ macro_rules! index {
 
 #[derive(Clone)]
 pub struct Il2CppFunctions {
+  pub il2cpp_array_new: Option<FunctionPtr<fn(*const Il2CppClass, usize) -> *const Il2CppArray>>,
+
   pub il2cpp_assembly_get_image: Option<FunctionPtr<fn(*const Il2CppAssembly) -> *const Il2CppImage>>,
 
+  pub il2cpp_class_get_events: Option<FunctionPtr<fn(*const Il2CppClass, *const *const c_void) -> *const EventInfo>>,
   pub il2cpp_class_get_fields: Option<FunctionPtr<fn(*const Il2CppClass, *const *const c_void) -> *const FieldInfo>>,
+  pub il2cpp_class_get_nested_types: Option<FunctionPtr<fn(*const Il2CppClass, *const *const c_void) -> *const Il2CppClass>>,
   pub il2cpp_class_get_interfaces: Option<FunctionPtr<fn(*const Il2CppClass, *const *const c_void) -> *const Il2CppClass>>,
+  pub il2cpp_class_get_properties: Option<FunctionPtr<fn(*const Il2CppClass, *const *const c_void) -> *const PropertyInfo>>,
   pub il2cpp_class_get_methods: Option<FunctionPtr<fn(*const Il2CppClass, *const *const c_void) -> *const MethodInfo>>,
+  pub il2cpp_class_get_method_from_name: Option<FunctionPtr<fn(*const Il2CppClass, *const c_char, i32) -> *const MethodInfo>>,
   pub il2cpp_class_get_name: Option<FunctionPtr<fn(*const Il2CppClass) -> *const c_char>>,
   pub il2cpp_class_get_namespace: Option<FunctionPtr<fn(*const Il2CppClass) -> *const c_char>>,
   pub il2cpp_class_get_parent: Option<FunctionPtr<fn(*const Il2CppClass) -> *const Il2CppClass>>,
+  pub il2cpp_class_get_declaring_type: Option<FunctionPtr<fn(*const Il2CppClass) -> *const Il2CppClass>>,
   pub il2cpp_class_is_valuetype: Option<FunctionPtr<fn(*const Il2CppClass) -> bool>>,
   pub il2cpp_class_get_flags: Option<FunctionPtr<fn(*const Il2CppClass) -> i32>>,
   pub il2cpp_class_from_type: Option<FunctionPtr<fn(*const Il2CppType) -> *const Il2CppClass>>,
+  pub il2cpp_class_get_type: Option<FunctionPtr<fn(*const Il2CppClass) -> *const Il2CppType>>,
   pub il2cpp_class_is_enum: Option<FunctionPtr<fn(*const Il2CppClass) -> bool>>,
+  pub il2cpp_class_instance_size: Option<FunctionPtr<fn(*const Il2CppClass) -> i32>>,
+  pub il2cpp_class_value_size: Option<FunctionPtr<fn(*const Il2CppClass, *mut u32) -> i32>>,
+  pub il2cpp_class_array_element_size: Option<FunctionPtr<fn(*const Il2CppClass) -> i32>>,
+  pub il2cpp_class_get_image: Option<FunctionPtr<fn(*const Il2CppClass) -> *const Il2CppImage>>,
+  pub il2cpp_class_get_data_size: Option<FunctionPtr<fn(*const Il2CppClass) -> i32>>,
+  pub il2cpp_class_get_static_field_data: Option<FunctionPtr<fn(*const Il2CppClass) -> *const c_void>>,
 
   pub il2cpp_domain_get: Option<FunctionPtr<fn() -> *const Il2CppDomain>>,
   pub il2cpp_domain_get_assemblies: Option<FunctionPtr<fn(*const Il2CppDomain, *const usize) -> *const *const Il2CppAssembly>>,
@@ -31,12 +45,30 @@ pub struct Il2CppFunctions {
   pub il2cpp_field_get_name: Option<FunctionPtr<fn(*const FieldInfo) -> *const c_char>>,
   pub il2cpp_field_get_offset: Option<FunctionPtr<fn(*const FieldInfo) -> usize>>,
   pub il2cpp_field_get_type: Option<FunctionPtr<fn(*const FieldInfo) -> *const Il2CppType>>,
+  pub il2cpp_field_static_get_value: Option<FunctionPtr<fn(*const FieldInfo, *mut c_void)>>,
 
   pub il2cpp_method_get_return_type: Option<FunctionPtr<fn(*const MethodInfo) -> *const Il2CppType>>,
   pub il2cpp_method_get_name: Option<FunctionPtr<fn(*const MethodInfo) -> *const c_char>>,
+  pub il2cpp_method_is_generic: Option<FunctionPtr<fn(*const MethodInfo) -> bool>>,
   pub il2cpp_method_get_param_count: Option<FunctionPtr<fn(*const MethodInfo) -> u32>>,
   pub il2cpp_method_get_param: Option<FunctionPtr<fn(*const MethodInfo, u32) -> *const Il2CppType>>,
+  pub il2cpp_method_get_flags: Option<FunctionPtr<fn(*const MethodInfo, *mut u32) -> u32>>,
+  pub il2cpp_method_get_param_name: Option<FunctionPtr<fn(*const MethodInfo, u32) -> *const c_char>>,
+  pub il2cpp_method_get_object: Option<FunctionPtr<fn(*const MethodInfo, *const Il2CppClass) -> *const Il2CppObject>>,
+
+  pub il2cpp_resolve_icall: Option<FunctionPtr<fn(*const c_char) -> *const c_void>>,
+
+  pub il2cpp_runtime_invoke: Option<FunctionPtr<fn(*const MethodInfo, *const c_void, *const *const c_void, *mut *const Il2CppObject) -> *const Il2CppObject>>,
+
+  pub il2cpp_thread_attach: Option<FunctionPtr<fn(*const Il2CppDomain) -> *const Il2CppThread>>,
+
+  pub il2cpp_property_get_flags: Option<FunctionPtr<fn(*const PropertyInfo) -> u32>>,
+  pub il2cpp_property_get_get_method: Option<FunctionPtr<fn(*const PropertyInfo) -> *const MethodInfo>>,
+  pub il2cpp_property_get_set_method: Option<FunctionPtr<fn(*const PropertyInfo) -> *const MethodInfo>>,
+  pub il2cpp_property_get_name: Option<FunctionPtr<fn(*const PropertyInfo) -> *const c_char>>,
 
+  pub il2cpp_type_get_object: Option<FunctionPtr<fn(*const Il2CppType) -> *const Il2CppObject>>,
+  pub il2cpp_type_get_type: Option<FunctionPtr<fn(*const Il2CppType) -> i32>>,
   pub il2cpp_type_get_name: Option<FunctionPtr<fn(*const Il2CppType) -> *const c_char>>,
   pub il2cpp_type_is_byref: Option<FunctionPtr<fn(*const Il2CppType) -> bool>>,
   pub il2cpp_type_get_attrs: Option<FunctionPtr<fn(*const Il2CppType) -> u32>>,
@@ -56,27 +88,55 @@ impl Il2CppFunctions {
       il2cpp_class_get_methods: index!(funcs, 35),
       il2cpp_class_get_name: index!(funcs, 37),
       il2cpp_class_get_namespace: index!(funcs, 39),
+      il2cpp_class_get_declaring_type: index!(funcs, 41),
       il2cpp_domain_get: index!(funcs, 63),
       il2cpp_domain_get_assemblies: index!(funcs, 65),
+      // Not in the table as far as anyone verified, Il2CppApi::new looks it up in GameAssembly's exports instead
+      il2cpp_thread_attach: None,
       il2cpp_method_get_name: index!(funcs, 117),
       il2cpp_image_get_class_count: index!(funcs, 169),
       il2cpp_image_get_class: index!(funcs, 170),
 
       // Optional for the C# dumper
+      il2cpp_array_new: index!(funcs, 17),
+      il2cpp_class_get_events: index!(funcs, 30),
       il2cpp_class_get_fields: index!(funcs, 31),
+      il2cpp_class_get_nested_types: index!(funcs, 32),
       il2cpp_class_get_interfaces: index!(funcs, 33),
+      il2cpp_class_get_properties: index!(funcs, 34),
+      il2cpp_class_get_method_from_name: index!(funcs, 36),
       il2cpp_class_get_parent: index!(funcs, 40),
+      il2cpp_class_instance_size: index!(funcs, 42),
       il2cpp_class_is_valuetype: index!(funcs, 43),
+      il2cpp_class_value_size: index!(funcs, 44),
       il2cpp_class_get_flags: index!(funcs, 45),
+      il2cpp_class_array_element_size: index!(funcs, 48),
       il2cpp_class_from_type: index!(funcs, 49),
-      il2cpp_class_is_enum: index!(funcs, 53), 
+      il2cpp_class_get_type: index!(funcs, 50),
+      il2cpp_class_is_enum: index!(funcs, 53),
+      il2cpp_class_get_image: index!(funcs, 54),
+      il2cpp_class_get_data_size: index!(funcs, 57),
+      il2cpp_class_get_static_field_data: index!(funcs, 58),
       il2cpp_field_get_flags: index!(funcs, 72),
       il2cpp_field_get_name: index!(funcs, 73),
       il2cpp_field_get_offset: index!(funcs, 75),
       il2cpp_field_get_type: index!(funcs, 76),
+      il2cpp_field_static_get_value: index!(funcs, 81),
       il2cpp_method_get_return_type: index!(funcs, 116),
       il2cpp_method_get_param_count: index!(funcs, 123),
+      il2cpp_method_get_object: index!(funcs, 119),
+      il2cpp_method_is_generic: index!(funcs, 120),
       il2cpp_method_get_param: index!(funcs, 124),
+      il2cpp_method_get_flags: index!(funcs, 127),
+      il2cpp_method_get_param_name: index!(funcs, 129),
+      il2cpp_property_get_flags: index!(funcs, 137),
+      il2cpp_property_get_get_method: index!(funcs, 138),
+      il2cpp_property_get_set_method: index!(funcs, 139),
+      il2cpp_property_get_name: index!(funcs, 140),
+      il2cpp_resolve_icall: index!(funcs, 11),
+      il2cpp_runtime_invoke: index!(funcs, 156),
+      il2cpp_type_get_object: index!(funcs, 158),
+      il2cpp_type_get_type: index!(funcs, 159),
       il2cpp_type_get_name: index!(funcs, 161),
       il2cpp_type_is_byref: index!(funcs, 162),
       il2cpp_type_get_attrs: index!(funcs, 163),
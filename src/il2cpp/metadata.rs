@@ -0,0 +1,88 @@
+use std::{ffi::c_void, mem::size_of};
+
+use super::{module::Module, types::*};
+
+// Offset of fieldOffsetsCount inside Il2CppMetadataRegistration, in pointer sized slots
+const FIELD_OFFSETS_SLOT: usize = 10;
+
+// The registration isn't exported, so it's found the same way Il2CppDumper does it: both the field offsets
+// and the type definition sizes are stored as a count followed by a pointer, and both counts equal the
+// number of type definitions, which gives a fairly unique [count, pointer, count, pointer] pattern
+pub fn find_metadata_registration(module: &Module, type_count: usize) -> Option<*const Il2CppMetadataRegistration> {
+  if type_count == 0 {
+    return None;
+  }
+
+  for (start, size) in module.readable_regions() {
+    let words = unsafe { std::slice::from_raw_parts(start as *const usize, size / size_of::<usize>()) };
+
+    for (i, window) in words.windows(4).enumerate() {
+      if window[0] != type_count || window[2] != type_count || !module.contains(window[1]) || !module.contains(window[3]) {
+        continue;
+      }
+
+      if i < FIELD_OFFSETS_SLOT {
+        continue;
+      }
+
+      let registration = (start + (i - FIELD_OFFSETS_SLOT) * size_of::<usize>()) as *const Il2CppMetadataRegistration;
+
+      if is_valid(module, registration) {
+        return Some(registration);
+      }
+    }
+  }
+
+  None
+}
+
+fn is_valid(module: &Module, registration: *const Il2CppMetadataRegistration) -> bool {
+  let registration = unsafe { &*registration };
+
+  registration.generic_insts_count >= 0
+    && registration.method_specs_count >= 0
+    && registration.types_count > 0
+    && module.contains(registration.generic_insts as usize)
+    && module.contains(registration.method_specs as usize)
+    && module.contains(registration.types as usize)
+}
+
+pub fn method_specs(registration: &Il2CppMetadataRegistration) -> &'static [Il2CppMethodSpec] {
+  unsafe { std::slice::from_raw_parts(registration.method_specs, registration.method_specs_count as usize) }
+}
+
+// Type arguments of a generic instantiation, None when the index is -1 or out of range
+pub fn generic_inst(registration: &Il2CppMetadataRegistration, index: i32) -> Option<&'static [*const Il2CppType]> {
+  if index < 0 || index >= registration.generic_insts_count {
+    return None;
+  }
+
+  unsafe {
+    let inst = *registration.generic_insts.add(index as usize);
+
+    if inst.is_null() {
+      return None;
+    }
+
+    Some(std::slice::from_raw_parts((*inst).type_argv, (*inst).type_argc as usize))
+  }
+}
+
+// Addresses of the slots code loads metadata through, each one is filled in the first time a method using it runs
+pub fn metadata_usage_slots(registration: &Il2CppMetadataRegistration) -> &'static [*mut *const c_void] {
+  unsafe { std::slice::from_raw_parts(registration.metadata_usages, registration.metadata_usages_count) }
+}
+
+// kIl2CppMetadataUsageStringLiteral, the usage type of slots holding a string literal
+pub const USAGE_STRING_LITERAL: u32 = 5;
+
+// Slots start out as (usage type << 29) | (index << 1) | 1 and are overwritten with the pointer once resolved,
+// returns the usage type and the index of the literal, type or method in the metadata
+pub fn decode_usage(value: usize) -> Option<(u32, u32)> {
+  if value & 1 == 0 || value > u32::MAX as usize {
+    return None;
+  }
+
+  let token = value as u32;
+  Some((token >> 29, (token & 0x1ffffffe) >> 1))
+}
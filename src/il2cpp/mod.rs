@@ -1,5 +1,6 @@
 pub mod api;
 pub mod functions;
+pub mod metadata;
 pub mod types;
 pub mod module;
 pub mod constants;
\ No newline at end of file
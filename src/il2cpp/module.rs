@@ -1,6 +1,6 @@
-use std::{ffi::{c_void, CString}, marker::PhantomData, mem::size_of, ops::Deref, path::PathBuf};
+use std::{ffi::{c_void, CString}, fs, io, marker::PhantomData, mem::size_of, ops::Deref, path::PathBuf, ptr::null_mut};
 use thiserror::Error;
-use winapi::{shared::minwindef::FALSE, um::{libloaderapi::LoadLibraryA, processthreadsapi::GetCurrentProcess, psapi::{GetModuleInformation, MODULEINFO}}};
+use winapi::{shared::minwindef::{FALSE, HMODULE, MAX_PATH}, um::{libloaderapi::{GetModuleFileNameA, GetModuleHandleExA, GetProcAddress, LoadLibraryA, GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS, GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT}, memoryapi::VirtualQuery, processthreadsapi::GetCurrentProcess, psapi::{GetModuleInformation, MODULEINFO}, winnt::{MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_GUARD, PAGE_NOACCESS}}};
 
 #[derive(Debug, Error)]
 pub enum ModuleError {
@@ -16,7 +16,10 @@ pub enum ModuleError {
 
 pub struct Module {
   pub handle: *mut c_void,
-  pub size: usize
+  pub size: usize,
+  // Address the module was linked at, what disassemblers show unless the module is rebased
+  pub image_base: usize,
+  pub path: PathBuf
 }
 
 impl Module {
@@ -41,10 +44,131 @@ impl Module {
 
       Ok(Module {
         handle: handle.cast(),
-        size
+        size,
+        image_base: image_base(handle as usize),
+        path
       })
     }
   }
+
+  // Address of a function the module exports by name, None when it isn't exported
+  pub fn export(&self, name: &str) -> Option<*const c_void> {
+    let native = CString::new(name).ok()?;
+    let address = unsafe { GetProcAddress(self.handle.cast(), native.as_ptr()) };
+
+    if address.is_null() { None } else { Some(address as *const c_void) }
+  }
+
+  // Pointer sized values as the file on disk has them, before the runtime wrote to them, uninitialized data reads as 0
+  pub fn file_values(&self, rvas: &[usize]) -> io::Result<Vec<usize>> {
+    let file = fs::read(&self.path)?;
+    let sections = file_sections(&file).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid PE headers"))?;
+
+    Ok(rvas.iter().map(|&rva| {
+      let offset = sections.iter()
+        .find(|section| rva >= section.virtual_address && rva - section.virtual_address + size_of::<usize>() <= section.raw_size)
+        .map(|section| section.raw_offset + rva - section.virtual_address);
+
+      match offset.and_then(|offset| file.get(offset..offset + size_of::<usize>())) {
+        Some(bytes) => usize::from_le_bytes(bytes.try_into().unwrap()),
+        None => 0
+      }
+    }).collect())
+  }
+
+  pub fn contains(&self, address: usize) -> bool {
+    let start = self.handle as usize;
+    address >= start && address < start + self.size
+  }
+
+  // Committed pages of the module that can be read without faulting, as (start, size) pairs
+  pub fn readable_regions(&self) -> Vec<(usize, usize)> {
+    let mut regions = vec![];
+    let mut address = self.handle as usize;
+    let end = self.handle as usize + self.size;
+
+    while address < end {
+      let mut info: MEMORY_BASIC_INFORMATION = unsafe { std::mem::zeroed() };
+      let res = unsafe { VirtualQuery(address as *const c_void as _, &mut info, size_of::<MEMORY_BASIC_INFORMATION>()) };
+
+      if res == 0 || info.RegionSize == 0 {
+        break;
+      }
+
+      let region_end = (info.BaseAddress as usize + info.RegionSize).min(end);
+
+      if info.State == MEM_COMMIT && info.Protect & (PAGE_NOACCESS | PAGE_GUARD) == 0 {
+        regions.push((address, region_end - address));
+      }
+
+      address = region_end;
+    }
+
+    regions
+  }
+}
+
+// ImageBase from the optional header of a loaded module, it's 8 bytes in PE32+ and 4 bytes in PE32
+fn image_base(handle: usize) -> usize {
+  unsafe {
+    let nt_headers = handle + *((handle + 0x3c) as *const u32) as usize;
+    let optional_header = nt_headers + 0x18;
+
+    match *(optional_header as *const u16) {
+      0x20b => *((optional_header + 0x18) as *const u64) as usize,
+      _ => *((optional_header + 0x1c) as *const u32) as usize
+    }
+  }
+}
+
+struct FileSection {
+  virtual_address: usize,
+  raw_size: usize,
+  raw_offset: usize
+}
+
+// Section table of a PE file, it follows the optional header whose size is in the file header
+fn file_sections(file: &[u8]) -> Option<Vec<FileSection>> {
+  let read_u16 = |offset: usize| file.get(offset..offset + 2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize);
+  let read_u32 = |offset: usize| file.get(offset..offset + 4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize);
+
+  let nt_headers = read_u32(0x3c)?;
+  let section_count = read_u16(nt_headers + 6)?;
+  let section_table = nt_headers + 0x18 + read_u16(nt_headers + 0x14)?;
+
+  (0..section_count).map(|i| {
+    let section = section_table + i * 40;
+
+    Some(FileSection {
+      virtual_address: read_u32(section + 12)?,
+      raw_size: read_u32(section + 16)?,
+      raw_offset: read_u32(section + 20)?
+    })
+  }).collect()
+}
+
+// Finds the loaded module an address belongs to, returns its file name and base address
+pub fn module_from_address(address: usize) -> Option<(String, usize)> {
+  unsafe {
+    let mut handle: HMODULE = null_mut();
+    let flags = GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT;
+
+    if GetModuleHandleExA(flags, address as *const _, &mut handle) == FALSE || handle.is_null() {
+      return None;
+    }
+
+    let mut buffer = [0u8; MAX_PATH];
+    let length = GetModuleFileNameA(handle, buffer.as_mut_ptr() as *mut _, MAX_PATH as u32) as usize;
+
+    if length == 0 {
+      return None;
+    }
+
+    let path = String::from_utf8_lossy(&buffer[..length]).to_string();
+    let name = path.rsplit('\\').next().unwrap_or(&path).to_string();
+
+    Some((name, handle as usize))
+  }
 }
 
 pub struct FunctionPtr<T> {
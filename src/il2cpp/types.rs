@@ -1,10 +1,49 @@
-use std::ffi::c_void;
+use std::ffi::{c_char, c_void};
 
+// Only the members that aren't exposed through exported functions, laid out as in Unity 2019.4
 #[repr(C)]
-pub struct Il2CppClass;
+pub struct Il2CppClass {
+  _pad0: [u8; 0x11a],
+  pub vtable_count: u16,
+  _pad1: [u8; 0xc],
+  pub vtable: [VirtualInvokeData; 0]
+}
 
 #[repr(C)]
-pub struct Il2CppType;
+pub struct VirtualInvokeData {
+  pub method_ptr: *const c_void,
+  pub method: *const MethodInfo
+}
+
+// The bit fields after the data (attrs, type, num_mods, byref, pinned) are read through exported functions
+#[repr(C)]
+pub struct Il2CppType {
+  pub data: *const c_void,
+  pub bits: u32
+}
+
+#[repr(C)]
+pub struct Il2CppArrayType {
+  pub etype: *const Il2CppType,
+  pub rank: u8,
+  pub numsizes: u8,
+  pub numlobounds: u8,
+  pub sizes: *const i32,
+  pub lobounds: *const i32
+}
+
+#[repr(C)]
+pub struct Il2CppGenericContext {
+  pub class_inst: *const Il2CppGenericInst,
+  pub method_inst: *const Il2CppGenericInst
+}
+
+#[repr(C)]
+pub struct Il2CppGenericClass {
+  pub type_definition_index: i32,
+  pub context: Il2CppGenericContext,
+  pub cached_class: *const Il2CppClass
+}
 
 #[repr(C)]
 pub struct MethodInfo {
@@ -17,6 +56,19 @@ pub struct MethodInfo {
 #[repr(C)]
 pub struct FieldInfo;
 
+#[repr(C)]
+pub struct PropertyInfo;
+
+#[repr(C)]
+pub struct EventInfo {
+  pub name: *const c_char,
+  pub event_type: *const Il2CppType,
+  pub parent: *const Il2CppClass,
+  pub add: *const MethodInfo,
+  pub remove: *const MethodInfo,
+  pub raise: *const MethodInfo
+}
+
 #[repr(C)]
 pub struct Il2CppAssembly;
 
@@ -24,4 +76,85 @@ pub struct Il2CppAssembly;
 pub struct Il2CppDomain;
 
 #[repr(C)]
-pub struct Il2CppImage;
\ No newline at end of file
+pub struct Il2CppThread;
+
+#[repr(C)]
+pub struct Il2CppImage;
+
+#[repr(C)]
+pub struct Il2CppObject {
+  pub klass: *const Il2CppClass,
+  pub monitor: *const c_void
+}
+
+#[repr(C)]
+pub struct Il2CppString {
+  pub object: Il2CppObject,
+  pub length: i32,
+  pub chars: [u16; 0]
+}
+
+#[repr(C)]
+pub struct Il2CppArray {
+  pub object: Il2CppObject,
+  pub bounds: *const c_void,
+  pub max_length: usize,
+  pub vector: [*const Il2CppObject; 0]
+}
+
+#[repr(C)]
+pub struct Il2CppReflectionType {
+  pub object: Il2CppObject,
+  pub type_: *const Il2CppType
+}
+
+#[repr(C)]
+pub struct Il2CppReflectionMethod {
+  pub object: Il2CppObject,
+  pub method: *const MethodInfo,
+  pub name: *const Il2CppString,
+  pub reftype: *const Il2CppReflectionType
+}
+
+#[repr(C)]
+pub struct Il2CppReflectionField {
+  pub object: Il2CppObject,
+  pub klass: *const Il2CppClass,
+  pub field: *const FieldInfo,
+  pub name: *const Il2CppString,
+  pub type_: *const Il2CppReflectionType,
+  pub attrs: u32
+}
+
+#[repr(C)]
+pub struct Il2CppGenericInst {
+  pub type_argc: u32,
+  pub type_argv: *const *const Il2CppType
+}
+
+#[repr(C)]
+pub struct Il2CppMethodSpec {
+  pub method_definition_index: i32,
+  pub class_index_index: i32,
+  pub method_index_index: i32
+}
+
+#[repr(C)]
+pub struct Il2CppMetadataRegistration {
+  pub generic_classes_count: i32,
+  pub generic_classes: *const *const c_void,
+  pub generic_insts_count: i32,
+  pub generic_insts: *const *const Il2CppGenericInst,
+  pub generic_method_table_count: i32,
+  pub generic_method_table: *const c_void,
+  pub types_count: i32,
+  pub types: *const *const Il2CppType,
+  pub method_specs_count: i32,
+  pub method_specs: *const Il2CppMethodSpec,
+  pub field_offsets_count: i32,
+  pub field_offsets: *const *const i32,
+  pub type_definitions_sizes_count: i32,
+  pub type_definitions_sizes: *const *const c_void,
+  pub metadata_usages_count: usize,
+  pub metadata_usages: *const *mut *const c_void
+}
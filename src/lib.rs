@@ -1,3 +1,5 @@
 pub mod base;
+pub mod emit;
 pub mod il2cpp;
+pub mod model;
 pub mod outputs;
\ No newline at end of file
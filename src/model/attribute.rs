@@ -0,0 +1,167 @@
+use std::{ffi::c_void, ptr::null};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, constants::*, types::*};
+use super::{class::{class_full_name, method_rva}, reflection, type_name::TypeName, value::Value};
+
+pub struct Attribute {
+  pub type_name: String,
+  // The attribute class's own constructor, not the per member generator Il2CppDumper reports
+  pub constructor_rva: Option<usize>,
+  pub arguments: Vec<(String, Value)>
+}
+
+impl Attribute {
+  pub fn from_class(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<Attribute>, Il2CppError> {
+    let object = il2cpp.type_get_object(il2cpp.class_get_type(class)?)?;
+    Attribute::collect(il2cpp, unsafe { &*object })
+  }
+
+  pub fn from_method(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<Vec<Attribute>, Il2CppError> {
+    Attribute::collect(il2cpp, unsafe { &*il2cpp.method_get_object(method)? })
+  }
+
+  // Takes the field's reflection object, see reflection::field_objects
+  pub fn from_field(il2cpp: &Il2CppApi, object: &Il2CppObject) -> Result<Vec<Attribute>, Il2CppError> {
+    Attribute::collect(il2cpp, object)
+  }
+
+  // One list per parameter, in declaration order
+  pub fn from_params(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<Vec<Vec<Attribute>>, Il2CppError> {
+    let object = il2cpp.method_get_object(method)?;
+    let mut attributes = vec![];
+
+    for param in reflection::invoke_array(il2cpp, unsafe { &*object }, "GetParameters")? {
+      attributes.push(if param.is_null() { vec![] } else { Attribute::collect(il2cpp, unsafe { &*param })? });
+    }
+
+    Ok(attributes)
+  }
+
+  // Name without the conventional suffix, the way it's written in C#
+  pub fn short_name(&self) -> &str {
+    self.type_name.strip_suffix("Attribute").unwrap_or(&self.type_name)
+  }
+
+  // Types, members and parameters all expose GetCustomAttributes(bool inherit) through reflection
+  fn collect(il2cpp: &Il2CppApi, object: &Il2CppObject) -> Result<Vec<Attribute>, Il2CppError> {
+    let inherit = false;
+    let objects = reflection::invoke_array_with(il2cpp, object, "GetCustomAttributes", &[&inherit as *const bool as *const c_void])?;
+
+    let mut attributes = vec![];
+
+    for object in objects {
+      if object.is_null() {
+        continue;
+      }
+
+      let class = unsafe { (*object).klass };
+
+      attributes.push(Attribute {
+        type_name: class_full_name(il2cpp, class)?,
+        constructor_rva: constructor_rva(il2cpp, class)?,
+        arguments: collect_arguments(il2cpp, class, object)?
+      });
+    }
+
+    Ok(attributes)
+  }
+}
+
+// Only reported when there's a single constructor, otherwise there's no telling which one was used
+fn constructor_rva(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Option<usize>, Il2CppError> {
+  let mut constructors = vec![];
+
+  let method_iter: *const c_void = null();
+
+  while let Some(method) = il2cpp.class_get_methods(class, &method_iter)? {
+    if il2cpp.method_get_name(method)? == ".ctor" {
+      constructors.push(method);
+    }
+  }
+
+  Ok(match constructors.as_slice() {
+    [constructor] => method_rva(il2cpp, unsafe { &**constructor }),
+    _ => None
+  })
+}
+
+// Constructor arguments aren't kept around by the runtime, but they usually end up in public fields and
+// properties, which are also what named arguments set. Only fields are read, calling getters would run game
+// code for every attribute, so properties are limited to auto-properties through their backing fields
+fn collect_arguments(il2cpp: &Il2CppApi, class: *const Il2CppClass, object: *const Il2CppObject) -> Result<Vec<(String, Value)>, Il2CppError> {
+  let mut arguments: Vec<(String, Value)> = vec![];
+  let mut current = class;
+
+  // Members of System.Attribute itself, like TypeId, aren't arguments
+  while !current.is_null() && !is_system_attribute(il2cpp, current)? {
+    let properties = public_properties(il2cpp, current)?;
+    let field_iter: *const c_void = null();
+
+    while let Some(field) = il2cpp.class_get_fields(current, &field_iter)? {
+      let flags = il2cpp.field_get_flags(field)?;
+
+      if flags & (FIELD_ATTRIBUTE_STATIC | FIELD_ATTRIBUTE_LITERAL) != 0 {
+        continue;
+      }
+
+      let field_name = il2cpp.field_get_name(field)?;
+
+      let name = match backing_field_property(&field_name) {
+        Some(property) if properties.iter().any(|name| name == property) => property.to_string(),
+        Some(_) => continue,
+        None if flags & FIELD_ATTRIBUTE_FIELD_ACCESS_MASK == FIELD_ATTRIBUTE_PUBLIC => field_name,
+        None => continue
+      };
+
+      // Overridden properties show up again on the base class
+      if arguments.iter().any(|(argument, _)| *argument == name) {
+        continue;
+      }
+
+      let field_type = il2cpp.field_get_type(field)?;
+      let type_name = TypeName::from_type(il2cpp, field_type)?.to_string();
+
+      if let Some(value) = Value::read_instance(il2cpp, object, field, field_type, &type_name)? {
+        arguments.push((name, value));
+      }
+    }
+
+    current = match il2cpp.class_get_parent(current) {
+      Ok(parent) => parent,
+      Err(Il2CppError::ReturnedNull(_)) => break,
+      Err(error) => return Err(error)
+    };
+  }
+
+  Ok(arguments)
+}
+
+// Properties with a public instance getter, the ones a named argument could have set
+fn public_properties(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<String>, Il2CppError> {
+  let mut properties = vec![];
+  let property_iter: *const c_void = null();
+
+  while let Some(property) = il2cpp.class_get_properties(class, &property_iter)? {
+    let getter = match il2cpp.property_get_get_method(property)? {
+      Some(getter) => getter,
+      None => continue
+    };
+
+    let flags = unsafe { (*getter).flags } as i32;
+
+    if flags & METHOD_ATTRIBUTE_STATIC == 0 && flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK == METHOD_ATTRIBUTE_PUBLIC {
+      properties.push(il2cpp.property_get_name(property)?);
+    }
+  }
+
+  Ok(properties)
+}
+
+// The compiler stores an auto-property in <Name>k__BackingField
+fn backing_field_property(name: &str) -> Option<&str> {
+  name.strip_prefix('<')?.strip_suffix(">k__BackingField")
+}
+
+fn is_system_attribute(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<bool, Il2CppError> {
+  Ok(il2cpp.class_get_namespace(class)? == "System" && il2cpp.class_get_name(class)? == "Attribute")
+}
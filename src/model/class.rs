@@ -0,0 +1,608 @@
+use std::{ffi::{c_void, CStr}, ptr::null};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, constants::*, module::module_from_address, types::*};
+use super::{attribute::Attribute, generic::GenericParam, icall::InternalCall, layout::{Layout, VtableSlot}, reflection, type_name::TypeName, value::{read_field_default, read_param_defaults, Value}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClassKind {
+  Class,
+  Struct,
+  Enum,
+  Interface
+}
+
+impl ClassKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ClassKind::Class => "class",
+      ClassKind::Struct => "struct",
+      ClassKind::Enum => "enum",
+      ClassKind::Interface => "interface"
+    }
+  }
+}
+
+pub struct Field {
+  pub handle: *const FieldInfo,
+  pub name: String,
+  pub type_name: TypeName,
+  pub flags: i32,
+  pub offset: usize,
+  pub thread_static: bool,
+  pub address: Option<usize>,
+  pub value: Option<Value>,
+  pub snapshot: Option<Value>,
+  pub attributes: Vec<Attribute>
+}
+
+pub struct Param {
+  pub name: String,
+  pub type_name: TypeName,
+  pub default: Option<Value>,
+  pub attrs: i32,
+  pub byref: bool,
+  pub attributes: Vec<Attribute>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeKind {
+  Compiled,
+  Abstract,
+  NoCode,
+  // Implemented in a module other than GameAssembly, e.g. an internal call into UnityPlayer
+  External,
+  // Generic definitions only have code for their instantiations
+  UnresolvedGeneric
+}
+
+impl CodeKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      CodeKind::Compiled => "compiled",
+      CodeKind::Abstract => "abstract",
+      CodeKind::NoCode => "no code",
+      CodeKind::External => "external",
+      CodeKind::UnresolvedGeneric => "unresolved generic"
+    }
+  }
+}
+
+// Where the code of a method lives, the RVA is relative to the module it was found in
+#[derive(Clone, PartialEq, Eq)]
+pub struct MethodCode {
+  pub kind: CodeKind,
+  pub module: Option<String>,
+  pub rva: Option<usize>
+}
+
+pub struct Method {
+  pub handle: *const MethodInfo,
+  pub name: String,
+  pub generic_params: Vec<GenericParam>,
+  pub flags: i32,
+  pub rva: Option<usize>,
+  pub code: MethodCode,
+  pub internal_call: Option<InternalCall>,
+  pub return_type: TypeName,
+  pub return_byref: bool,
+  pub params: Vec<Param>,
+  pub attributes: Vec<Attribute>
+}
+
+pub struct Accessor {
+  pub handle: *const MethodInfo,
+  pub name: String,
+  pub flags: i32,
+  pub code: MethodCode
+}
+
+pub struct Property {
+  pub name: String,
+  pub type_name: TypeName,
+  pub getter: Option<Accessor>,
+  pub setter: Option<Accessor>
+}
+
+pub struct Event {
+  pub name: String,
+  pub type_name: TypeName,
+  pub add: Option<Accessor>,
+  pub remove: Option<Accessor>,
+  pub raise: Option<Accessor>
+}
+
+pub struct Class {
+  pub namespace: String,
+  pub name: String,
+  pub generic_params: Vec<GenericParam>,
+  pub declaring_type: Option<String>,
+  pub flags: i32,
+  pub kind: ClassKind,
+  pub layout: Layout,
+  pub static_fields: Option<usize>,
+  pub vtable: Vec<VtableSlot>,
+  pub attributes: Vec<Attribute>,
+  pub parent: Option<TypeName>,
+  pub interfaces: Vec<TypeName>,
+  pub fields: Vec<Field>,
+  pub properties: Vec<Property>,
+  pub events: Vec<Event>,
+  pub methods: Vec<Method>,
+  pub nested_types: Vec<Class>
+}
+
+impl Class {
+  pub fn collect(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Self, Il2CppError> {
+    let namespace = class_namespace(il2cpp, class)?;
+    let name = il2cpp.class_get_name(class)?;
+
+    // Only generic definitions carry the arity in their name. The list includes the parameters of the
+    // declaring types as well, Outer`1+Inner`1 has both T and U, see declared_generic_params
+    let generic_params = if name.contains('`') {
+      logged(GenericParam::from_class(il2cpp, class), "generic parameters", &name)
+    }
+    else {
+      vec![]
+    };
+
+    let declaring_type = match il2cpp.class_get_declaring_type(class)? {
+      Some(declaring_type) => Some(class_full_name(il2cpp, declaring_type)?),
+      None => None
+    };
+    let flags = il2cpp.class_get_flags(class)?;
+
+    let is_valuetype = il2cpp.class_is_valuetype(class)?;
+    let is_enum = il2cpp.class_is_enum(class)?;
+
+    let kind = if flags & TYPE_ATTRIBUTE_INTERFACE != 0 {
+      ClassKind::Interface
+    }
+    else if is_enum {
+      ClassKind::Enum
+    }
+    else if is_valuetype {
+      ClassKind::Struct
+    }
+    else {
+      ClassKind::Class
+    };
+
+    let parent = match il2cpp.class_get_parent(class) {
+      Ok(parent) => Some(TypeName::from_class(il2cpp, parent)?),
+      Err(_) => None
+    };
+
+    let mut interfaces = vec![];
+    let interface_iter: *const c_void = null();
+
+    while let Some(interface) = il2cpp.class_get_interfaces(class, &interface_iter)? {
+      interfaces.push(TypeName::from_class(il2cpp, interface)?);
+    }
+
+    let layout = Layout::collect(il2cpp, class)?;
+    let vtable = collect_vtable(il2cpp, class)?;
+    let static_fields = il2cpp.class_get_static_field_data(class)?.map(|data| data as usize);
+
+    let attributes = Attribute::from_class(il2cpp, class).unwrap_or_default();
+
+    let fields = collect_fields(il2cpp, class, static_fields, is_valuetype, is_enum)?;
+    let properties = collect_properties(il2cpp, class)?;
+    let events = collect_events(il2cpp, class)?;
+    let methods = collect_methods(il2cpp, class)?;
+
+    let mut nested_types = vec![];
+    let nested_iter: *const c_void = null();
+
+    while let Some(nested_type) = il2cpp.class_get_nested_types(class, &nested_iter)? {
+      nested_types.push(Class::collect(il2cpp, nested_type)?);
+    }
+
+    Ok(Class {
+      namespace,
+      name,
+      generic_params,
+      declaring_type,
+      flags,
+      kind,
+      layout,
+      static_fields,
+      vtable,
+      attributes,
+      parent,
+      interfaces,
+      fields,
+      properties,
+      events,
+      methods,
+      nested_types
+    })
+  }
+
+  pub fn full_name(&self) -> String {
+    match &self.declaring_type {
+      Some(declaring_type) => format!("{}.{}", declaring_type, self.name),
+      None if self.namespace.is_empty() => self.name.clone(),
+      None => format!("{}.{}", self.namespace, self.name)
+    }
+  }
+
+  // The parameters the type declares itself, the arity in its name, without the ones it shares with its declaring types
+  pub fn declared_generic_params(&self) -> &[GenericParam] {
+    let arity = self.name.rsplit_once('`').and_then(|(_, arity)| arity.parse().ok()).unwrap_or(0);
+    &self.generic_params[self.generic_params.len().saturating_sub(arity)..]
+  }
+
+  // Name as written in C#, with the arity replaced by the generic parameters, e.g. List<T>
+  pub fn display_name(&self) -> String {
+    let name = self.name.split('`').next().unwrap_or(&self.name);
+    let generic_params = self.declared_generic_params();
+
+    if generic_params.is_empty() {
+      return name.to_string();
+    }
+
+    let params: Vec<String> = generic_params.iter()
+      .map(|param| format!("{}{}", param.variance(), param.name))
+      .collect();
+
+    format!("{}<{}>", name, params.join(", "))
+  }
+
+  // Reads the current values of static fields, including the ones of nested types, only primitives,
+  // enums and strings can be represented, anything else is left empty
+  pub fn snapshot_static_fields(&mut self, il2cpp: &Il2CppApi) {
+    for field in &mut self.fields {
+      if field.flags & FIELD_ATTRIBUTE_STATIC == 0 || field.flags & FIELD_ATTRIBUTE_LITERAL != 0 {
+        continue;
+      }
+
+      field.snapshot = il2cpp.field_get_type(field.handle)
+        .and_then(|field_type| Value::read(il2cpp, field.handle, field_type, &field.type_name.to_string()))
+        .ok()
+        .flatten()
+        .filter(|value| *value != Value::Null || field.type_name.is_primitive(IL2CPP_TYPE_STRING));
+    }
+
+    for nested_type in &mut self.nested_types {
+      nested_type.snapshot_static_fields(il2cpp);
+    }
+  }
+
+  // Sorts members and nested types by name, overloads by their parameter types, and drops the heap
+  // addresses of static fields, which change every run, so dumps of the same build are identical.
+  // Fields are sorted by offset instead, instance fields first, since their order is the type's layout
+  pub fn make_deterministic(&mut self) {
+    self.static_fields = None;
+
+    for field in &mut self.fields {
+      field.address = None;
+    }
+
+    // The sorts are stable, so members with the same name keep their metadata order
+    self.fields.sort_by_key(|field| (field.flags & FIELD_ATTRIBUTE_STATIC != 0, field.offset));
+    self.properties.sort_by(|a, b| a.name.cmp(&b.name));
+    self.events.sort_by(|a, b| a.name.cmp(&b.name));
+    self.methods.sort_by_cached_key(|method| (method.name.clone(), method.params.iter().map(|param| param.type_name.to_string()).collect::<Vec<_>>()));
+    self.nested_types.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for nested_type in &mut self.nested_types {
+      nested_type.make_deterministic();
+    }
+  }
+
+  // The type that backs the enum, taken from the instance field every enum has
+  pub fn enum_underlying_type(&self) -> Option<&TypeName> {
+    if self.kind != ClassKind::Enum {
+      return None;
+    }
+
+    underlying_type(&self.fields)
+  }
+}
+
+// Nested types report an empty namespace, the one of the outermost declaring type is used instead
+pub fn class_namespace(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<String, Il2CppError> {
+  match il2cpp.class_get_declaring_type(class)? {
+    Some(declaring_type) => class_namespace(il2cpp, declaring_type),
+    None => il2cpp.class_get_namespace(class)
+  }
+}
+
+// Reflection can fail on single types without the rest of the dump being affected, so it's reported and left empty
+fn logged<T: Default>(result: Result<T, Il2CppError>, what: &str, owner: &str) -> T {
+  result.unwrap_or_else(|error| {
+    println!("failed to read the {} of {}: {}", what, owner, error);
+    T::default()
+  })
+}
+
+// Namespace qualified name with nested types separated by a dot, e.g. RPG.Client.Outer.Inner
+pub fn class_full_name(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<String, Il2CppError> {
+  let name = il2cpp.class_get_name(class)?;
+
+  if let Some(declaring_type) = il2cpp.class_get_declaring_type(class)? {
+    return Ok(format!("{}.{}", class_full_name(il2cpp, declaring_type)?, name));
+  }
+
+  let namespace = il2cpp.class_get_namespace(class)?;
+  Ok(if namespace.is_empty() { name } else { format!("{}.{}", namespace, name) })
+}
+
+fn underlying_type(fields: &[Field]) -> Option<&TypeName> {
+  fields.iter()
+    .find(|field| field.flags & FIELD_ATTRIBUTE_STATIC == 0)
+    .map(|field| &field.type_name)
+}
+
+fn collect_fields(il2cpp: &Il2CppApi, class: *const Il2CppClass, static_fields: Option<usize>, is_valuetype: bool, is_enum: bool) -> Result<Vec<Field>, Il2CppError> {
+  let mut fields = vec![];
+  let mut literals = vec![];
+  let mut defaults = vec![];
+
+  // Attributes and default values are only reachable through the fields' reflection objects
+  let objects = reflection::field_objects(il2cpp, class).unwrap_or_default();
+
+  let field_iter: *const c_void = null();
+
+  while let Some(field) = il2cpp.class_get_fields(class, &field_iter)? {
+    let flags = il2cpp.field_get_flags(field)?;
+    let field_type = il2cpp.field_get_type(field)?;
+    let mut offset = il2cpp.field_get_offset(field)?;
+
+    if is_valuetype && flags & FIELD_ATTRIBUTE_STATIC == 0 && offset > 0 {
+      offset -= 0x10;
+    }
+
+    // Thread static fields live in per-thread storage, so they have no fixed address
+    let thread_static = flags & FIELD_ATTRIBUTE_STATIC != 0 && offset == THREAD_STATIC_FIELD_OFFSET;
+
+    let address = match static_fields {
+      Some(data) if flags & (FIELD_ATTRIBUTE_STATIC | FIELD_ATTRIBUTE_LITERAL) == FIELD_ATTRIBUTE_STATIC && !thread_static => Some(data + offset),
+      _ => None
+    };
+
+    if flags & FIELD_ATTRIBUTE_LITERAL != 0 {
+      literals.push((fields.len(), field, field_type));
+    }
+    else if flags & FIELD_ATTRIBUTE_HAS_DEFAULT != 0 {
+      defaults.push((fields.len(), field));
+    }
+
+    fields.push(Field {
+      handle: field,
+      name: il2cpp.field_get_name(field)?,
+      type_name: TypeName::from_type(il2cpp, field_type)?,
+      flags,
+      offset,
+      thread_static,
+      address,
+      value: None,
+      snapshot: None,
+      attributes: objects.get(&field).map(|&object| Attribute::from_field(il2cpp, unsafe { &*object }).unwrap_or_default()).unwrap_or_default()
+    });
+  }
+
+  // Enum members are typed as the enum itself, so they have to be read as the underlying type instead
+  let underlying_type = if is_enum { underlying_type(&fields).map(TypeName::to_string) } else { None };
+
+  // A value that fails to read is left empty so that the outputs can point it out instead of aborting the dump
+  for (index, field, field_type) in literals {
+    let value = match &underlying_type {
+      Some(underlying_type) => Value::read_primitive(il2cpp, field, underlying_type),
+      None => Value::read(il2cpp, field, field_type, &fields[index].type_name.to_string())
+    };
+
+    fields[index].value = value.ok().flatten();
+  }
+
+  for (index, field) in defaults {
+    fields[index].value = objects.get(&field).and_then(|&object| read_field_default(il2cpp, unsafe { &*object }).ok().flatten());
+  }
+
+  Ok(fields)
+}
+
+fn collect_accessor(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<Option<Accessor>, Il2CppError> {
+  if method.is_null() {
+    return Ok(None);
+  }
+
+  Ok(Some(Accessor {
+    handle: method,
+    name: il2cpp.method_get_name(method)?,
+    flags: unsafe { (*method).flags } as i32,
+    code: method_code(il2cpp, unsafe { &*method })?
+  }))
+}
+
+fn collect_properties(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<Property>, Il2CppError> {
+  let mut properties = vec![];
+
+  let property_iter: *const c_void = null();
+
+  while let Some(property) = il2cpp.class_get_properties(class, &property_iter)? {
+    let get_method = il2cpp.property_get_get_method(property)?;
+    let set_method = il2cpp.property_get_set_method(property)?;
+
+    // The property type is whatever the getter returns or the setter takes as its last parameter
+    let property_type = match (get_method, set_method) {
+      (Some(get_method), _) => il2cpp.method_get_return_type(get_method)?,
+      (None, Some(set_method)) => {
+        let param_count = il2cpp.method_get_param_count(set_method)?;
+        il2cpp.method_get_param(set_method, param_count.saturating_sub(1))?
+      },
+      (None, None) => continue
+    };
+
+    properties.push(Property {
+      name: il2cpp.property_get_name(property)?,
+      type_name: TypeName::from_type(il2cpp, property_type)?,
+      getter: collect_accessor(il2cpp, get_method.unwrap_or(null()))?,
+      setter: collect_accessor(il2cpp, set_method.unwrap_or(null()))?
+    });
+  }
+
+  Ok(properties)
+}
+
+// Only called after Layout::collect, which initializes the class
+fn collect_vtable(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<VtableSlot>, Il2CppError> {
+  VtableSlot::collect(il2cpp, unsafe { &*class })
+}
+
+fn collect_events(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<Event>, Il2CppError> {
+  let mut events = vec![];
+
+  let event_iter: *const c_void = null();
+
+  // The runtime doesn't export any accessors for events, so EventInfo is read directly
+  while let Some(event) = il2cpp.class_get_events(class, &event_iter)? {
+    let event = unsafe { &*event };
+
+    if event.name.is_null() || event.event_type.is_null() {
+      continue;
+    }
+
+    events.push(Event {
+      name: unsafe { CStr::from_ptr(event.name) }.to_str()?.to_string(),
+      type_name: TypeName::from_type(il2cpp, event.event_type)?,
+      add: collect_accessor(il2cpp, event.add)?,
+      remove: collect_accessor(il2cpp, event.remove)?,
+      raise: collect_accessor(il2cpp, event.raise)?
+    });
+  }
+
+  Ok(events)
+}
+
+// Only set for code inside GameAssembly, see method_code for everything else
+pub fn method_rva(il2cpp: &Il2CppApi, method: &MethodInfo) -> Option<usize> {
+  let pointer = method.method_pointer as usize;
+  if il2cpp.game_assembly.contains(pointer) { pointer.checked_sub(il2cpp.game_assembly.handle as usize) } else { None }
+}
+
+impl MethodCode {
+  // The RVA when the code is in GameAssembly, what method_rva returns
+  pub fn compiled_rva(&self) -> Option<usize> {
+    self.rva.filter(|_| self.kind == CodeKind::Compiled)
+  }
+}
+
+pub fn method_code(il2cpp: &Il2CppApi, method: &MethodInfo) -> Result<MethodCode, Il2CppError> {
+  let pointer = method.method_pointer as usize;
+
+  if il2cpp.game_assembly.contains(pointer) {
+    return Ok(MethodCode {
+      kind: CodeKind::Compiled,
+      module: Some("GameAssembly.dll".to_string()),
+      rva: Some(pointer - il2cpp.game_assembly.handle as usize)
+    });
+  }
+
+  if pointer != 0 {
+    let (module, rva) = match module_from_address(pointer) {
+      Some((module, base)) => (Some(module), Some(pointer - base)),
+      None => (None, None)
+    };
+
+    return Ok(MethodCode { kind: CodeKind::External, module, rva });
+  }
+
+  let kind = if method.flags as i32 & METHOD_ATTRIBUTE_ABSTRACT != 0 {
+    CodeKind::Abstract
+  }
+  else if il2cpp.method_is_generic(method)? || il2cpp.class_get_name(method.klass)?.contains('`') {
+    CodeKind::UnresolvedGeneric
+  }
+  else {
+    CodeKind::NoCode
+  };
+
+  Ok(MethodCode { kind, module: None, rva: None })
+}
+
+// Names and types as the runtime reports them, defaults and attributes are read separately through reflection
+pub fn collect_params(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<Vec<Param>, Il2CppError> {
+  let mut params = vec![];
+  let param_count = il2cpp.method_get_param_count(method)?;
+
+  for i in 0..param_count {
+    let param = il2cpp.method_get_param(method, i)?;
+
+    // Some compiler generated methods have unnamed parameters
+    let name = match il2cpp.method_get_param_name(method, i) {
+      Err(Il2CppError::ReturnedNull(_)) => String::new(),
+      name => name?
+    };
+
+    params.push(Param {
+      name,
+      type_name: TypeName::from_type(il2cpp, param)?,
+      default: None,
+      attrs: il2cpp.type_get_attrs(param)? as i32,
+      byref: il2cpp.type_is_byref(param)?,
+      attributes: vec![]
+    });
+  }
+
+  Ok(params)
+}
+
+fn collect_methods(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<Method>, Il2CppError> {
+  let mut methods = vec![];
+
+  let method_iter: *const c_void = null();
+
+  while let Some(method) = il2cpp.class_get_methods(class, &method_iter)? {
+    let rva = method_rva(il2cpp, unsafe { &*method });
+    let flags = unsafe { (*method).flags } as i32;
+    let return_type = il2cpp.method_get_return_type(method)?;
+
+    let mut params = collect_params(il2cpp, method)?;
+
+    if !params.is_empty() {
+      let attributes = Attribute::from_params(il2cpp, method).unwrap_or_default();
+
+      for (param, attributes) in params.iter_mut().zip(attributes) {
+        param.attributes = attributes;
+      }
+    }
+
+    // A value that fails to read is left empty, same as with constants
+    if params.iter().any(|param| param.attrs & PARAM_ATTRIBUTE_HAS_DEFAULT != 0) {
+      let defaults = read_param_defaults(il2cpp, method).unwrap_or_default();
+
+      for (param, default) in params.iter_mut().zip(defaults) {
+        if param.attrs & PARAM_ATTRIBUTE_HAS_DEFAULT != 0 {
+          param.default = default;
+        }
+      }
+    }
+
+    // Not every runtime exports what's needed to resolve them, those methods just aren't annotated
+    let internal_call = InternalCall::resolve(il2cpp, unsafe { &*method }).ok().flatten();
+
+    let generic_params = if il2cpp.method_is_generic(method)? {
+      logged(GenericParam::from_method(il2cpp, method), "generic parameters", &il2cpp.method_get_name(method)?)
+    }
+    else {
+      vec![]
+    };
+
+    methods.push(Method {
+      handle: method,
+      name: il2cpp.method_get_name(method)?,
+      generic_params,
+      flags,
+      rva,
+      code: method_code(il2cpp, unsafe { &*method })?,
+      internal_call,
+      return_type: TypeName::from_type(il2cpp, return_type)?,
+      return_byref: il2cpp.type_is_byref(return_type)?,
+      params,
+      attributes: Attribute::from_method(il2cpp, method).unwrap_or_default()
+    });
+  }
+
+  Ok(methods)
+}
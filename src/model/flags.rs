@@ -0,0 +1,87 @@
+use crate::il2cpp::constants::*;
+
+// Name, mask and the value the masked flags must have for the name to apply
+pub type FlagTable = &'static [(&'static str, i32, i32)];
+
+pub const TYPE_FLAGS: FlagTable = &[
+  ("not_public", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NOT_PUBLIC),
+  ("public", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_PUBLIC),
+  ("nested_public", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NESTED_PUBLIC),
+  ("nested_private", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NESTED_PRIVATE),
+  ("nested_family", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NESTED_FAMILY),
+  ("nested_assembly", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NESTED_ASSEMBLY),
+  ("nested_fam_and_assem", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NESTED_FAM_AND_ASSEM),
+  ("nested_fam_or_assem", TYPE_ATTRIBUTE_VISIBILITY_MASK, TYPE_ATTRIBUTE_NESTED_FAM_OR_ASSEM),
+  ("sequential_layout", TYPE_ATTRIBUTE_LAYOUT_MASK, TYPE_ATTRIBUTE_SEQUENTIAL_LAYOUT),
+  ("explicit_layout", TYPE_ATTRIBUTE_LAYOUT_MASK, TYPE_ATTRIBUTE_EXPLICIT_LAYOUT),
+  ("interface", TYPE_ATTRIBUTE_CLASS_SEMANTIC_MASK, TYPE_ATTRIBUTE_INTERFACE),
+  ("abstract", TYPE_ATTRIBUTE_ABSTRACT, TYPE_ATTRIBUTE_ABSTRACT),
+  ("sealed", TYPE_ATTRIBUTE_SEALED, TYPE_ATTRIBUTE_SEALED),
+  ("special_name", TYPE_ATTRIBUTE_SPECIAL_NAME, TYPE_ATTRIBUTE_SPECIAL_NAME),
+  ("rt_special_name", TYPE_ATTRIBUTE_RT_SPECIAL_NAME, TYPE_ATTRIBUTE_RT_SPECIAL_NAME),
+  ("import", TYPE_ATTRIBUTE_IMPORT, TYPE_ATTRIBUTE_IMPORT),
+  ("serializable", TYPE_ATTRIBUTE_SERIALIZABLE, TYPE_ATTRIBUTE_SERIALIZABLE),
+  ("unicode_class", TYPE_ATTRIBUTE_STRING_FORMAT_MASK, TYPE_ATTRIBUTE_UNICODE_CLASS),
+  ("auto_class", TYPE_ATTRIBUTE_STRING_FORMAT_MASK, TYPE_ATTRIBUTE_AUTO_CLASS),
+  ("before_field_init", TYPE_ATTRIBUTE_BEFORE_FIELD_INIT, TYPE_ATTRIBUTE_BEFORE_FIELD_INIT),
+  ("forwarder", TYPE_ATTRIBUTE_FORWARDER, TYPE_ATTRIBUTE_FORWARDER),
+  ("has_security", TYPE_ATTRIBUTE_HAS_SECURITY, TYPE_ATTRIBUTE_HAS_SECURITY)
+];
+
+pub const FIELD_FLAGS: FlagTable = &[
+  ("compiler_controlled", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_COMPILER_CONTROLLED),
+  ("private", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_PRIVATE),
+  ("fam_and_assem", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_FAM_AND_ASSEM),
+  ("assembly", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_ASSEMBLY),
+  ("family", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_FAMILY),
+  ("fam_or_assem", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_FAM_OR_ASSEM),
+  ("public", FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, FIELD_ATTRIBUTE_PUBLIC),
+  ("static", FIELD_ATTRIBUTE_STATIC, FIELD_ATTRIBUTE_STATIC),
+  ("init_only", FIELD_ATTRIBUTE_INIT_ONLY, FIELD_ATTRIBUTE_INIT_ONLY),
+  ("literal", FIELD_ATTRIBUTE_LITERAL, FIELD_ATTRIBUTE_LITERAL),
+  ("not_serialized", FIELD_ATTRIBUTE_NOT_SERIALIZED, FIELD_ATTRIBUTE_NOT_SERIALIZED),
+  ("special_name", FIELD_ATTRIBUTE_SPECIAL_NAME, FIELD_ATTRIBUTE_SPECIAL_NAME),
+  ("rt_special_name", FIELD_ATTRIBUTE_RT_SPECIAL_NAME, FIELD_ATTRIBUTE_RT_SPECIAL_NAME),
+  ("pinvoke_impl", FIELD_ATTRIBUTE_PINVOKE_IMPL, FIELD_ATTRIBUTE_PINVOKE_IMPL),
+  ("has_field_marshal", FIELD_ATTRIBUTE_HAS_FIELD_MARSHAL, FIELD_ATTRIBUTE_HAS_FIELD_MARSHAL),
+  ("has_default", FIELD_ATTRIBUTE_HAS_DEFAULT, FIELD_ATTRIBUTE_HAS_DEFAULT),
+  ("has_field_rva", FIELD_ATTRIBUTE_HAS_FIELD_RVA, FIELD_ATTRIBUTE_HAS_FIELD_RVA)
+];
+
+pub const METHOD_FLAGS: FlagTable = &[
+  ("compiler_controlled", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_COMPILER_CONTROLLED),
+  ("private", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_PRIVATE),
+  ("fam_and_assem", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_FAM_AND_ASSEM),
+  ("assembly", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_ASSEM),
+  ("family", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_FAMILY),
+  ("fam_or_assem", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_FAM_OR_ASSEM),
+  ("public", METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK, METHOD_ATTRIBUTE_PUBLIC),
+  ("static", METHOD_ATTRIBUTE_STATIC, METHOD_ATTRIBUTE_STATIC),
+  ("final", METHOD_ATTRIBUTE_FINAL, METHOD_ATTRIBUTE_FINAL),
+  ("virtual", METHOD_ATTRIBUTE_VIRTUAL, METHOD_ATTRIBUTE_VIRTUAL),
+  ("hide_by_sig", METHOD_ATTRIBUTE_HIDE_BY_SIG, METHOD_ATTRIBUTE_HIDE_BY_SIG),
+  ("new_slot", METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK, METHOD_ATTRIBUTE_NEW_SLOT),
+  ("strict", METHOD_ATTRIBUTE_STRICT, METHOD_ATTRIBUTE_STRICT),
+  ("abstract", METHOD_ATTRIBUTE_ABSTRACT, METHOD_ATTRIBUTE_ABSTRACT),
+  ("special_name", METHOD_ATTRIBUTE_SPECIAL_NAME, METHOD_ATTRIBUTE_SPECIAL_NAME),
+  ("rt_special_name", METHOD_ATTRIBUTE_RT_SPECIAL_NAME, METHOD_ATTRIBUTE_RT_SPECIAL_NAME),
+  ("pinvoke_impl", METHOD_ATTRIBUTE_PINVOKE_IMPL, METHOD_ATTRIBUTE_PINVOKE_IMPL),
+  ("unmanaged_export", METHOD_ATTRIBUTE_UNMANAGED_EXPORT, METHOD_ATTRIBUTE_UNMANAGED_EXPORT),
+  ("has_security", METHOD_ATTRIBUTE_HAS_SECURITY, METHOD_ATTRIBUTE_HAS_SECURITY),
+  ("require_sec_object", METHOD_ATTRIBUTE_REQUIRE_SEC_OBJECT, METHOD_ATTRIBUTE_REQUIRE_SEC_OBJECT)
+];
+
+pub const PARAM_FLAGS: FlagTable = &[
+  ("in", PARAM_ATTRIBUTE_IN, PARAM_ATTRIBUTE_IN),
+  ("out", PARAM_ATTRIBUTE_OUT, PARAM_ATTRIBUTE_OUT),
+  ("optional", PARAM_ATTRIBUTE_OPTIONAL, PARAM_ATTRIBUTE_OPTIONAL),
+  ("has_default", PARAM_ATTRIBUTE_HAS_DEFAULT, PARAM_ATTRIBUTE_HAS_DEFAULT),
+  ("has_field_marshal", PARAM_ATTRIBUTE_HAS_FIELD_MARSHAL, PARAM_ATTRIBUTE_HAS_FIELD_MARSHAL)
+];
+
+// Every name in the table together with whether it applies, so consumers never have to know the masks
+pub fn decode(flags: i32, table: FlagTable) -> Vec<(&'static str, bool)> {
+  table.iter()
+    .map(|&(name, mask, value)| (name, flags & mask == value))
+    .collect()
+}
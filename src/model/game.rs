@@ -0,0 +1,76 @@
+use crate::il2cpp::api::{Il2CppApi, Il2CppError};
+use super::{class::Class, generic::{self, GenericInstance, GenericInstances}, image::Image};
+
+// An image with its top-level classes, nested classes are kept inside their declaring class
+pub struct ImageClasses {
+  pub image: Image,
+  pub classes: Vec<Class>
+}
+
+// Everything the outputs are built from, collected once up front since every pass over the runtime takes a while
+pub struct Game {
+  pub images: Vec<ImageClasses>,
+  pub instances: GenericInstances,
+  // Preferred base of GameAssembly, VAs are RVAs relative to it
+  pub image_base: usize
+}
+
+impl Game {
+  // Static field values change while the game runs, so they're only read when asked for
+  pub fn collect(il2cpp: &Il2CppApi, snapshot: bool, sorted: bool) -> Result<Game, Il2CppError> {
+    let mut images = vec![];
+
+    for image in Image::collect_ordered(il2cpp, sorted)? {
+      let mut classes = vec![];
+
+      for class in image.top_level_classes_ordered(il2cpp, sorted)? {
+        let mut class = Class::collect(il2cpp, class)?;
+
+        if snapshot {
+          class.snapshot_static_fields(il2cpp);
+        }
+
+        if sorted {
+          class.make_deterministic();
+        }
+
+        classes.push(class);
+      }
+
+      images.push(ImageClasses { image, classes });
+    }
+
+    // Not every runtime exports what's needed to inflate them, methods are dumped without their instances then
+    let mut instances = GenericInstance::collect_all(il2cpp).unwrap_or_else(|error| {
+      println!("generic instances could not be collected: {}", error);
+      GenericInstances::new()
+    });
+
+    if sorted {
+      generic::sort_instances(&mut instances);
+    }
+
+    Ok(Game { images, instances, image_base: il2cpp.game_assembly.image_base })
+  }
+
+  // Every class including the nested ones, with the image it's defined in
+  pub fn all_classes(&self) -> Vec<(&Image, &Class)> {
+    let mut classes = vec![];
+
+    for entry in &self.images {
+      for class in &entry.classes {
+        push_classes(&entry.image, class, &mut classes);
+      }
+    }
+
+    classes
+  }
+}
+
+fn push_classes<'a>(image: &'a Image, class: &'a Class, classes: &mut Vec<(&'a Image, &'a Class)>) {
+  classes.push((image, class));
+
+  for nested_type in &class.nested_types {
+    push_classes(image, nested_type, classes);
+  }
+}
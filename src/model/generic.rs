@@ -0,0 +1,243 @@
+use std::{collections::HashMap, ffi::c_void, ptr::null};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, constants::*, metadata, types::*};
+use super::{class::{self, MethodCode, Param}, image::Image, reflection, type_name::TypeName};
+
+pub struct GenericParam {
+  pub name: String,
+  pub flags: i32,
+  pub constraints: Vec<TypeName>
+}
+
+impl GenericParam {
+  // The runtime doesn't export generic containers, so the parameters are read through
+  // reflection the same way Type.GetGenericArguments() would report them
+  pub fn from_class(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Vec<GenericParam>, Il2CppError> {
+    let class_type = il2cpp.class_get_type(class)?;
+    let object = il2cpp.type_get_object(class_type)?;
+    GenericParam::collect(il2cpp, object)
+  }
+
+  pub fn from_method(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<Vec<GenericParam>, Il2CppError> {
+    let object = il2cpp.method_get_object(method)?;
+    GenericParam::collect(il2cpp, object)
+  }
+
+  fn collect(il2cpp: &Il2CppApi, object: *const Il2CppObject) -> Result<Vec<GenericParam>, Il2CppError> {
+    let mut params = vec![];
+
+    for argument in reflection::invoke_array(il2cpp, unsafe { &*object }, "GetGenericArguments")? {
+      let argument = unsafe { &*argument };
+      let name = il2cpp.type_get_name(reflection::reflection_type(argument)?)?;
+      let flags = reflection::invoke_i32(il2cpp, argument, "get_GenericParameterAttributes")?;
+
+      let mut constraints = vec![];
+
+      for constraint in reflection::invoke_array(il2cpp, argument, "GetGenericParameterConstraints")? {
+        constraints.push(TypeName::from_type(il2cpp, reflection::reflection_type(unsafe { &*constraint })?)?);
+      }
+
+      params.push(GenericParam {
+        name,
+        flags,
+        constraints
+      });
+    }
+
+    Ok(params)
+  }
+
+  pub fn variance(&self) -> &'static str {
+    match self.flags & IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_VARIANCE_MASK {
+      IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_COVARIANT => "out ",
+      IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_CONTRAVARIANT => "in ",
+      _ => ""
+    }
+  }
+
+  // Constraints in the order C# expects them, empty when the parameter is unconstrained
+  pub fn constraint_list(&self) -> Vec<String> {
+    let mut constraints = vec![];
+    let is_struct = self.flags & IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_NOT_NULLABLE_VALUE_TYPE_CONSTRAINT != 0;
+
+    if is_struct {
+      constraints.push("struct".to_string());
+    }
+    else if self.flags & IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_REFERENCE_TYPE_CONSTRAINT != 0 {
+      constraints.push("class".to_string());
+    }
+
+    // The struct constraint is stored as an extra System.ValueType constraint
+    constraints.extend(self.constraints.iter()
+      .map(TypeName::to_string)
+      .filter(|constraint| !(is_struct && constraint == "System.ValueType")));
+
+    if !is_struct && self.flags & IL2CPP_GENERIC_PARAMETER_ATTRIBUTE_DEFAULT_CONSTRUCTOR_CONSTRAINT != 0 {
+      constraints.push("new()".to_string());
+    }
+
+    constraints
+  }
+}
+
+// A concrete instantiation of a generic method, compiled separately from its definition
+// Return and parameter types are the inflated ones, e.g. int where the definition has T
+pub struct GenericInstance {
+  pub handle: *const MethodInfo,
+  pub name: String,
+  pub code: MethodCode,
+  pub return_type: TypeName,
+  pub params: Vec<Param>
+}
+
+pub type GenericInstances = HashMap<*const MethodInfo, Vec<GenericInstance>>;
+
+impl GenericInstance {
+  // Every instantiation the game was built with is listed in the metadata registration's method specs,
+  // each one is inflated through reflection to get the runtime's own MethodInfo and code pointer
+  pub fn collect_all(il2cpp: &Il2CppApi) -> Result<GenericInstances, Il2CppError> {
+    let registration = il2cpp.metadata_registration()?;
+    let definitions = method_definitions(il2cpp)?;
+
+    let mut instances = GenericInstances::new();
+    let mut failed = 0;
+
+    for spec in metadata::method_specs(registration) {
+      let definition = match definitions.get(spec.method_definition_index as usize) {
+        Some(&definition) => definition,
+        None => continue
+      };
+
+      // Instantiations that violate a constraint or were stripped make reflection throw, those are skipped
+      let method = match instantiate(il2cpp, registration, definition, spec) {
+        Ok(method) => method,
+        Err(_) => continue
+      };
+
+      // Only this instance is lost when its name can't be read, the first error is reported for all of them
+      match GenericInstance::from_method(il2cpp, registration, method, spec) {
+        Ok(instance) => instances.entry(definition).or_default().push(instance),
+        Err(error) => {
+          if failed == 0 {
+            println!("failed to read a generic instance of {}: {}", il2cpp.method_get_name(definition)?, error);
+          }
+
+          failed += 1;
+        }
+      }
+    }
+
+    if failed > 1 {
+      println!("{} generic instances skipped", failed);
+    }
+
+    Ok(instances)
+  }
+
+  fn from_method(il2cpp: &Il2CppApi, registration: &Il2CppMetadataRegistration, method: *const MethodInfo, spec: &Il2CppMethodSpec) -> Result<GenericInstance, Il2CppError> {
+    let class_type = il2cpp.class_get_type(unsafe { (*method).klass })?;
+    let mut name = format!("{}::{}", TypeName::from_type(il2cpp, class_type)?, il2cpp.method_get_name(method)?);
+
+    if let Some(types) = metadata::generic_inst(registration, spec.method_index_index) {
+      let mut arguments = vec![];
+
+      for &type_ in types {
+        arguments.push(TypeName::from_type(il2cpp, type_)?.to_string());
+      }
+
+      name.push_str(format!("<{}>", arguments.join(", ")).as_str());
+    }
+
+    Ok(GenericInstance {
+      handle: method,
+      name,
+      code: class::method_code(il2cpp, unsafe { &*method })?,
+      return_type: TypeName::from_type(il2cpp, il2cpp.method_get_return_type(method)?)?,
+      params: class::collect_params(il2cpp, method)?
+    })
+  }
+}
+
+// Instances of every method by name, the order of the method specs changes whenever the game is rebuilt
+pub fn sort_instances(instances: &mut GenericInstances) {
+  for method_instances in instances.values_mut() {
+    method_instances.sort_by(|a, b| a.name.cmp(&b.name));
+  }
+}
+
+// Method definition indices follow the order of images, their type definitions and then their methods
+fn method_definitions(il2cpp: &Il2CppApi) -> Result<Vec<*const MethodInfo>, Il2CppError> {
+  let mut methods = vec![];
+
+  for image in Image::collect_all(il2cpp)? {
+    for class in image.classes(il2cpp)? {
+      let method_iter: *const c_void = null();
+
+      while let Some(method) = il2cpp.class_get_methods(class, &method_iter)? {
+        methods.push(method);
+      }
+    }
+  }
+
+  Ok(methods)
+}
+
+fn instantiate(il2cpp: &Il2CppApi, registration: &Il2CppMetadataRegistration, definition: *const MethodInfo, spec: &Il2CppMethodSpec) -> Result<*const MethodInfo, Il2CppError> {
+  let mut method = definition;
+
+  // Methods of generic types are looked up again on the inflated type, at the same position
+  if let Some(types) = metadata::generic_inst(registration, spec.class_index_index) {
+    let class = unsafe { (*definition).klass };
+    let object = il2cpp.type_get_object(il2cpp.class_get_type(class)?)?;
+    let arguments = reflection::type_array(il2cpp, types)?;
+
+    let inflated = reflection::invoke_with(il2cpp, unsafe { &*object }, "MakeGenericType", &[arguments as *const c_void])?;
+    let inflated_class = il2cpp.class_from_type(reflection::reflection_type(unsafe { &*inflated })?)?;
+
+    method = method_at(il2cpp, inflated_class, method_index(il2cpp, class, definition)?)?;
+  }
+
+  if let Some(types) = metadata::generic_inst(registration, spec.method_index_index) {
+    let object = il2cpp.method_get_object(method)?;
+    let arguments = reflection::type_array(il2cpp, types)?;
+
+    let inflated = reflection::invoke_with(il2cpp, unsafe { &*object }, "MakeGenericMethod", &[arguments as *const c_void])?;
+    method = unsafe { (*(inflated as *const Il2CppReflectionMethod)).method };
+  }
+
+  if method.is_null() || method == definition {
+    return Err(Il2CppError::ReturnedNull("instantiate"));
+  }
+
+  Ok(method)
+}
+
+fn method_index(il2cpp: &Il2CppApi, class: *const Il2CppClass, method: *const MethodInfo) -> Result<usize, Il2CppError> {
+  let method_iter: *const c_void = null();
+  let mut index = 0;
+
+  while let Some(current) = il2cpp.class_get_methods(class, &method_iter)? {
+    if current == method {
+      return Ok(index);
+    }
+
+    index += 1;
+  }
+
+  Err(Il2CppError::ReturnedNull("method_index"))
+}
+
+fn method_at(il2cpp: &Il2CppApi, class: *const Il2CppClass, index: usize) -> Result<*const MethodInfo, Il2CppError> {
+  let method_iter: *const c_void = null();
+  let mut current_index = 0;
+
+  while let Some(method) = il2cpp.class_get_methods(class, &method_iter)? {
+    if current_index == index {
+      return Ok(method);
+    }
+
+    current_index += 1;
+  }
+
+  Err(Il2CppError::ReturnedNull("method_at"))
+}
@@ -0,0 +1,74 @@
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, constants::*, module::module_from_address, types::*};
+use super::{type_name::{TypeName, TypeStyle}};
+
+// A method implemented natively, usually in UnityPlayer, and registered with the runtime under a name
+pub struct InternalCall {
+  // What the call is registered under, either the signature or just the name
+  pub name: String,
+  // Always the full signature, which tells overloads apart
+  pub signature: String,
+  pub module: Option<String>,
+  pub rva: Option<usize>
+}
+
+impl InternalCall {
+  // None for methods that aren't internal calls
+  pub fn resolve(il2cpp: &Il2CppApi, method: &MethodInfo) -> Result<Option<InternalCall>, Il2CppError> {
+    let (_, iflags) = il2cpp.method_get_flags(method)?;
+
+    if iflags as i32 & METHOD_IMPL_ATTRIBUTE_INTERNAL_CALL == 0 {
+      return Ok(None);
+    }
+
+    // Calls are registered either with their full signature or with just their name, the runtime tries both
+    let name = format!("{}::{}", icall_class_name(il2cpp, method.klass)?, il2cpp.method_get_name(method)?);
+    let signature = format!("{}({})", name, icall_params(il2cpp, method)?);
+
+    for candidate in [signature.clone(), name] {
+      if let Some(pointer) = il2cpp.resolve_icall(&candidate)? {
+        let pointer = pointer as usize;
+
+        let (module, rva) = if il2cpp.unity_player.contains(pointer) {
+          (Some("UnityPlayer.dll".to_string()), Some(pointer - il2cpp.unity_player.handle as usize))
+        }
+        else {
+          match module_from_address(pointer) {
+            Some((module, base)) => (Some(module), Some(pointer - base)),
+            None => (None, None)
+          }
+        };
+
+        return Ok(Some(InternalCall { name: candidate, signature, module, rva }));
+      }
+    }
+
+    // Registered lazily or stripped, the name is still useful to look up in the engine
+    Ok(Some(InternalCall { name: signature.clone(), signature, module: None, rva: None }))
+  }
+}
+
+// Nested types are separated with a slash in registered names, e.g. UnityEngine.Outer/Inner
+fn icall_class_name(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<String, Il2CppError> {
+  let name = il2cpp.class_get_name(class)?;
+
+  if let Some(declaring_type) = il2cpp.class_get_declaring_type(class)? {
+    return Ok(format!("{}/{}", icall_class_name(il2cpp, declaring_type)?, name));
+  }
+
+  let namespace = il2cpp.class_get_namespace(class)?;
+  Ok(if namespace.is_empty() { name } else { format!("{}.{}", namespace, name) })
+}
+
+// By-reference parameters end with an ampersand, e.g. UnityEngine.Vector3&
+fn icall_params(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<String, Il2CppError> {
+  let mut params = vec![];
+  let param_count = il2cpp.method_get_param_count(method)?;
+
+  for i in 0..param_count {
+    let param = il2cpp.method_get_param(method, i)?;
+    let suffix = if il2cpp.type_is_byref(param)? { "&" } else { "" };
+    params.push(format!("{}{}", TypeName::from_type(il2cpp, param)?.format(TypeStyle::FullyQualified), suffix));
+  }
+
+  Ok(params.join(","))
+}
@@ -0,0 +1,103 @@
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, types::*};
+
+pub struct Image {
+  pub index: usize,
+  pub name: String,
+  pub handle: *const Il2CppImage
+}
+
+impl Image {
+  pub fn collect_all(il2cpp: &Il2CppApi) -> Result<Vec<Image>, Il2CppError> {
+    let mut images = vec![];
+
+    let domain = il2cpp.domain_get()?;
+
+    let assembly_count: usize = 0;
+    let assemblies = il2cpp.domain_get_assemblies(domain, &assembly_count)?;
+
+    for i in 0..assembly_count {
+      let assembly = unsafe { *assemblies.add(i) };
+
+      if assembly.is_null() {
+        continue;
+      }
+
+      let handle = il2cpp.assembly_get_image(assembly)?;
+      let name = il2cpp.image_get_name(handle)?;
+
+      images.push(Image {
+        index: i,
+        name,
+        handle
+      });
+    }
+
+    Ok(images)
+  }
+
+  // Sorted by name when asked for, the order assemblies are loaded in isn't guaranteed to be the same between runs,
+  // and renumbered in that order, since the index is the load order as well
+  pub fn collect_ordered(il2cpp: &Il2CppApi, sorted: bool) -> Result<Vec<Image>, Il2CppError> {
+    let mut images = Image::collect_all(il2cpp)?;
+
+    if sorted {
+      images.sort_by(|a, b| a.name.cmp(&b.name).then(a.index.cmp(&b.index)));
+
+      for (index, image) in images.iter_mut().enumerate() {
+        image.index = index;
+      }
+    }
+
+    Ok(images)
+  }
+
+  pub fn classes(&self, il2cpp: &Il2CppApi) -> Result<Vec<*const Il2CppClass>, Il2CppError> {
+    let mut classes = vec![];
+    let class_count = il2cpp.image_get_class_count(self.handle)?;
+
+    for i in 0..class_count {
+      let class = il2cpp.image_get_class(self.handle, i)?;
+
+      if class.is_null() {
+        continue;
+      }
+
+      classes.push(class);
+    }
+
+    Ok(classes)
+  }
+
+  // Nested types are left out since they're collected as part of their declaring type
+  pub fn top_level_classes(&self, il2cpp: &Il2CppApi) -> Result<Vec<*const Il2CppClass>, Il2CppError> {
+    let mut classes = vec![];
+
+    for class in self.classes(il2cpp)? {
+      if il2cpp.class_get_declaring_type(class)?.is_none() {
+        classes.push(class);
+      }
+    }
+
+    Ok(classes)
+  }
+
+  // Sorted by namespace and name when asked for, the metadata order shifts whenever a type is added
+  pub fn top_level_classes_ordered(&self, il2cpp: &Il2CppApi, sorted: bool) -> Result<Vec<*const Il2CppClass>, Il2CppError> {
+    let classes = self.top_level_classes(il2cpp)?;
+
+    if !sorted {
+      return Ok(classes);
+    }
+
+    let mut named = vec![];
+
+    for class in classes {
+      named.push((il2cpp.class_get_namespace(class)?, il2cpp.class_get_name(class)?, class));
+    }
+
+    // The sort is stable, so types with the same name keep their metadata order
+    named.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+    Ok(named.into_iter().map(|(_, _, class)| class).collect())
+  }
+}
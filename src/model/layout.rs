@@ -0,0 +1,95 @@
+use std::{ffi::c_void, ptr::null};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, constants::*, types::*};
+use super::class::class_full_name;
+
+pub struct Layout {
+  pub instance_size: i32,
+  pub static_size: i32,
+  pub element_size: i32,
+  pub alignment: u32
+}
+
+impl Layout {
+  pub fn collect(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Layout, Il2CppError> {
+    // Asking for the instance size initializes the class, which is also what fills in its vtable
+    let instance_size = il2cpp.class_instance_size(class)?;
+    let (_, alignment) = il2cpp.class_value_size(class)?;
+
+    Ok(Layout {
+      instance_size,
+      static_size: il2cpp.class_get_data_size(class)?,
+      element_size: il2cpp.class_array_element_size(class)?,
+      alignment
+    })
+  }
+}
+
+pub struct VtableSlot {
+  pub slot: usize,
+  pub method: Option<String>,
+  pub rva: Option<usize>
+}
+
+impl VtableSlot {
+  // The vtable isn't exposed through any exported function, so it's read from the class itself,
+  // this has to happen after the class was initialized. Left empty when the count doesn't look right
+  pub fn collect(il2cpp: &Il2CppApi, class: &Il2CppClass) -> Result<Vec<VtableSlot>, Il2CppError> {
+    let count = match vtable_count(il2cpp, class)? {
+      Some(count) => count,
+      None => return Ok(vec![])
+    };
+
+    let entries = unsafe { std::slice::from_raw_parts(class.vtable.as_ptr(), count) };
+    let mut slots = vec![];
+
+    for (slot, entry) in entries.iter().enumerate() {
+      // Abstract slots have no method or just a stub pointer
+      let method = if entry.method.is_null() {
+        None
+      }
+      else {
+        let declaring_class = unsafe { (*entry.method).klass };
+        Some(format!("{}::{}", class_full_name(il2cpp, declaring_class)?, il2cpp.method_get_name(entry.method)?))
+      };
+
+      let pointer = entry.method_ptr as usize;
+
+      slots.push(VtableSlot {
+        slot,
+        method,
+        rva: if il2cpp.game_assembly.contains(pointer) { Some(pointer - il2cpp.game_assembly.handle as usize) } else { None }
+      });
+    }
+
+    Ok(slots)
+  }
+}
+
+// The count is read at a hard-coded offset, so it's checked against what the vtable has to hold before the
+// entries are trusted: every virtual method of the class gets a slot and the parent's slots come first
+fn vtable_count(il2cpp: &Il2CppApi, class: &Il2CppClass) -> Result<Option<usize>, Il2CppError> {
+  let count = class.vtable_count as usize;
+  let handle = class as *const Il2CppClass;
+
+  let mut virtual_methods = 0;
+  let method_iter: *const c_void = null();
+
+  while let Some(method) = il2cpp.class_get_methods(handle, &method_iter)? {
+    let (flags, _) = il2cpp.method_get_flags(method)?;
+
+    if flags as i32 & METHOD_ATTRIBUTE_VIRTUAL != 0 {
+      virtual_methods += 1;
+    }
+  }
+
+  if count < virtual_methods {
+    return Ok(None);
+  }
+
+  match il2cpp.class_get_parent(handle) {
+    Ok(parent) if unsafe { (*parent).vtable_count } as usize > count => Ok(None),
+    Ok(_) | Err(Il2CppError::ReturnedNull(_)) => Ok(Some(count)),
+    Err(error) => Err(error)
+  }
+}
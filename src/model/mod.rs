@@ -0,0 +1,12 @@
+pub mod attribute;
+pub mod class;
+pub mod flags;
+pub mod game;
+pub mod generic;
+pub mod icall;
+pub mod image;
+pub mod layout;
+pub mod reflection;
+pub mod type_name;
+pub mod usage;
+pub mod value;
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, ffi::c_void};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, types::*};
+
+// BindingFlags.Instance | Static | Public | NonPublic | DeclaredOnly
+const BINDING_FLAGS_DECLARED: i32 = 0x3e;
+
+// Calls a parameterless instance method on a managed object, looked up by name on the object's class
+pub fn invoke(il2cpp: &Il2CppApi, object: &Il2CppObject, name: &'static str) -> Result<*const Il2CppObject, Il2CppError> {
+  invoke_with(il2cpp, object, name, &[])
+}
+
+// Same as invoke, picking the overload by the number of arguments, reference type arguments are passed as is
+pub fn invoke_with(il2cpp: &Il2CppApi, object: &Il2CppObject, name: &'static str, args: &[*const c_void]) -> Result<*const Il2CppObject, Il2CppError> {
+  let method = il2cpp.class_get_method_from_name(object.klass, name, args.len() as i32)?
+    .ok_or(Il2CppError::ReturnedNull(name))?;
+
+  il2cpp.runtime_invoke(method, object as *const Il2CppObject as *const c_void, args.as_ptr())
+}
+
+pub fn invoke_array(il2cpp: &Il2CppApi, object: &Il2CppObject, name: &'static str) -> Result<Vec<*const Il2CppObject>, Il2CppError> {
+  invoke_array_with(il2cpp, object, name, &[])
+}
+
+pub fn invoke_array_with(il2cpp: &Il2CppApi, object: &Il2CppObject, name: &'static str, args: &[*const c_void]) -> Result<Vec<*const Il2CppObject>, Il2CppError> {
+  let array = invoke_with(il2cpp, object, name, args)? as *const Il2CppArray;
+
+  if array.is_null() {
+    return Err(Il2CppError::ReturnedNull(name));
+  }
+
+  let elements = unsafe { std::slice::from_raw_parts((*array).vector.as_ptr(), (*array).max_length) };
+  Ok(elements.to_vec())
+}
+
+// Unboxes the result of a method returning an int or an enum backed by one
+pub fn invoke_i32(il2cpp: &Il2CppApi, object: &Il2CppObject, name: &'static str) -> Result<i32, Il2CppError> {
+  let boxed = invoke(il2cpp, object, name)?;
+
+  if boxed.is_null() {
+    return Err(Il2CppError::ReturnedNull(name));
+  }
+
+  Ok(unsafe { *((boxed as usize + std::mem::size_of::<Il2CppObject>()) as *const i32) })
+}
+
+// Fields have no exported getter for their reflection object, so they're taken from the declaring type's fields
+pub fn field_objects(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<HashMap<*const FieldInfo, *const Il2CppObject>, Il2CppError> {
+  let object = il2cpp.type_get_object(il2cpp.class_get_type(class)?)?;
+  let flags = BINDING_FLAGS_DECLARED;
+  let mut objects = HashMap::new();
+
+  for field in invoke_array_with(il2cpp, unsafe { &*object }, "GetFields", &[&flags as *const i32 as *const c_void])? {
+    if !field.is_null() {
+      objects.insert(unsafe { (*(field as *const Il2CppReflectionField)).field }, field);
+    }
+  }
+
+  Ok(objects)
+}
+
+// System.Type objects wrap the runtime type they describe
+pub fn reflection_type(object: &Il2CppObject) -> Result<*const Il2CppType, Il2CppError> {
+  let type_ = unsafe { (*(object as *const Il2CppObject as *const Il2CppReflectionType)).type_ };
+
+  if type_.is_null() {
+    return Err(Il2CppError::ReturnedNull("Il2CppReflectionType::type"));
+  }
+
+  Ok(type_)
+}
+
+// Builds a System.Type[] out of runtime types, e.g. to pass type arguments to MakeGenericType
+pub fn type_array(il2cpp: &Il2CppApi, types: &[*const Il2CppType]) -> Result<*const Il2CppArray, Il2CppError> {
+  let mut objects = vec![];
+
+  for &type_ in types {
+    objects.push(il2cpp.type_get_object(type_)?);
+  }
+
+  let element_class = match objects.first() {
+    Some(&object) => system_type_class(il2cpp, unsafe { (*object).klass })?,
+    None => return Err(Il2CppError::ReturnedNull("type_array"))
+  };
+
+  let array = il2cpp.array_new(element_class, objects.len())?;
+
+  unsafe {
+    let vector = (*array).vector.as_ptr() as *mut *const Il2CppObject;
+    std::ptr::copy_nonoverlapping(objects.as_ptr(), vector, objects.len());
+  }
+
+  Ok(array)
+}
+
+// Type objects are instances of System.RuntimeType, the array has to be typed with its System.Type base
+fn system_type_class(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<*const Il2CppClass, Il2CppError> {
+  let mut current = class;
+
+  while !current.is_null() {
+    if il2cpp.class_get_namespace(current)? == "System" && il2cpp.class_get_name(current)? == "Type" {
+      return Ok(current);
+    }
+
+    current = match il2cpp.class_get_parent(current) {
+      Ok(parent) => parent,
+      Err(Il2CppError::ReturnedNull(_)) => break,
+      Err(error) => return Err(error)
+    };
+  }
+
+  Ok(class)
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, constants::*, types::*};
+use super::class::class_full_name;
+
+// Type code, C# keyword, fully qualified name and C name of every type with a dedicated type code
+const PRIMITIVES: &[(u8, &str, &str, &str)] = &[
+  (IL2CPP_TYPE_VOID, "void", "System.Void", "void"),
+  (IL2CPP_TYPE_BOOLEAN, "bool", "System.Boolean", "bool"),
+  (IL2CPP_TYPE_CHAR, "char", "System.Char", "uint16_t"),
+  (IL2CPP_TYPE_I1, "sbyte", "System.SByte", "int8_t"),
+  (IL2CPP_TYPE_U1, "byte", "System.Byte", "uint8_t"),
+  (IL2CPP_TYPE_I2, "short", "System.Int16", "int16_t"),
+  (IL2CPP_TYPE_U2, "ushort", "System.UInt16", "uint16_t"),
+  (IL2CPP_TYPE_I4, "int", "System.Int32", "int32_t"),
+  (IL2CPP_TYPE_U4, "uint", "System.UInt32", "uint32_t"),
+  (IL2CPP_TYPE_I8, "long", "System.Int64", "int64_t"),
+  (IL2CPP_TYPE_U8, "ulong", "System.UInt64", "uint64_t"),
+  (IL2CPP_TYPE_R4, "float", "System.Single", "float"),
+  (IL2CPP_TYPE_R8, "double", "System.Double", "double"),
+  (IL2CPP_TYPE_STRING, "string", "System.String", "System_String_o*"),
+  (IL2CPP_TYPE_TYPEDBYREF, "TypedReference", "System.TypedReference", "System_TypedReference_o"),
+  (IL2CPP_TYPE_I, "IntPtr", "System.IntPtr", "intptr_t"),
+  (IL2CPP_TYPE_U, "UIntPtr", "System.UIntPtr", "uintptr_t"),
+  (IL2CPP_TYPE_OBJECT, "object", "System.Object", "Il2CppObject*")
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeStyle {
+  // As written in C# source, with keywords for primitives and byref left to the declaration
+  CSharp,
+  // As used in C headers for native interop, e.g. System_String_o* or RPG_Client_Foo_array*
+  C,
+  // Namespace qualified everywhere, the way reflection reports types
+  FullyQualified
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeName {
+  Primitive(u8),
+  Class { name: String, valuetype: bool },
+  // Named after the generic definition, e.g. Dictionary`2.Enumerator, the arities tell which arguments go where
+  GenericInstance { name: String, valuetype: bool, arguments: Vec<TypeName> },
+  GenericParam(String),
+  Array { element: Box<TypeName>, rank: u8 },
+  Pointer(Box<TypeName>),
+  ByRef(Box<TypeName>),
+  // Anything without a structured representation (function pointers), named by the runtime
+  Other(String)
+}
+
+impl TypeName {
+  pub fn from_type(il2cpp: &Il2CppApi, type_: *const Il2CppType) -> Result<TypeName, Il2CppError> {
+    let type_name = TypeName::from_type_ignoring_byref(il2cpp, type_)?;
+
+    Ok(if il2cpp.type_is_byref(type_)? { TypeName::ByRef(Box::new(type_name)) } else { type_name })
+  }
+
+  pub fn from_class(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<TypeName, Il2CppError> {
+    TypeName::from_type(il2cpp, il2cpp.class_get_type(class)?)
+  }
+
+  fn from_type_ignoring_byref(il2cpp: &Il2CppApi, type_: *const Il2CppType) -> Result<TypeName, Il2CppError> {
+    let type_code = il2cpp.type_get_type(type_)?;
+    let data = unsafe { (*type_).data };
+
+    let type_name = match type_code {
+      code if PRIMITIVES.iter().any(|(primitive, ..)| *primitive == code) => TypeName::Primitive(code),
+      IL2CPP_TYPE_CLASS | IL2CPP_TYPE_VALUETYPE => {
+        let class = il2cpp.class_from_type(type_)?;
+
+        TypeName::Class {
+          name: class_full_name(il2cpp, class)?,
+          valuetype: type_code == IL2CPP_TYPE_VALUETYPE
+        }
+      },
+      IL2CPP_TYPE_GENERICINST => {
+        let class = il2cpp.class_from_type(type_)?;
+        let generic_class = data as *const Il2CppGenericClass;
+        let inst = unsafe { (*generic_class).context.class_inst };
+
+        let mut arguments = vec![];
+
+        if !inst.is_null() {
+          for &argument in unsafe { std::slice::from_raw_parts((*inst).type_argv, (*inst).type_argc as usize) } {
+            arguments.push(TypeName::from_type(il2cpp, argument)?);
+          }
+        }
+
+        TypeName::GenericInstance {
+          name: class_full_name(il2cpp, class)?,
+          valuetype: il2cpp.class_is_valuetype(class)?,
+          arguments
+        }
+      },
+      IL2CPP_TYPE_VAR | IL2CPP_TYPE_MVAR => TypeName::GenericParam(il2cpp.type_get_name(type_)?),
+      IL2CPP_TYPE_SZARRAY => TypeName::Array {
+        element: Box::new(TypeName::from_type(il2cpp, data as *const Il2CppType)?),
+        rank: 1
+      },
+      IL2CPP_TYPE_ARRAY => {
+        let array_type = data as *const Il2CppArrayType;
+
+        TypeName::Array {
+          element: Box::new(TypeName::from_type(il2cpp, unsafe { (*array_type).etype })?),
+          rank: unsafe { (*array_type).rank }
+        }
+      },
+      IL2CPP_TYPE_PTR => TypeName::Pointer(Box::new(TypeName::from_type(il2cpp, data as *const Il2CppType)?)),
+      _ => TypeName::Other(il2cpp.type_get_name(type_)?)
+    };
+
+    Ok(type_name)
+  }
+
+  pub fn format(&self, style: TypeStyle) -> String {
+    match (self, style) {
+      (TypeName::Primitive(code), _) => {
+        let (_, keyword, full_name, c_name) = PRIMITIVES.iter()
+          .find(|(primitive, ..)| primitive == code)
+          .copied()
+          .unwrap_or((*code, "?", "?", "?"));
+
+        match style {
+          TypeStyle::CSharp => keyword,
+          TypeStyle::C => c_name,
+          TypeStyle::FullyQualified => full_name
+        }.to_string()
+      },
+      (TypeName::Class { name, valuetype }, TypeStyle::C) => c_struct(name, *valuetype),
+      (TypeName::Class { name, .. }, _) => name.clone(),
+      (TypeName::GenericInstance { valuetype, .. }, TypeStyle::C) => c_struct(&self.format(TypeStyle::FullyQualified), *valuetype),
+      (TypeName::GenericInstance { name, arguments, .. }, _) => {
+        let arguments: Vec<String> = arguments.iter().map(|argument| argument.format(style)).collect();
+        with_arguments(name, &arguments)
+      },
+      // Generic code is shared between reference types, so only the erased type is known in C
+      (TypeName::GenericParam(_), TypeStyle::C) => "Il2CppObject*".to_string(),
+      (TypeName::GenericParam(name), _) => name.clone(),
+      (TypeName::Array { element, .. }, TypeStyle::C) => format!("{}_array*", mangle(&element.format(TypeStyle::FullyQualified))),
+      (TypeName::Array { element, rank }, _) => format!("{}[{}]", element.format(style), ",".repeat(rank.saturating_sub(1) as usize)),
+      (TypeName::Pointer(element), _) => format!("{}*", element.format(style)),
+      (TypeName::ByRef(element), TypeStyle::CSharp) => element.format(style),
+      (TypeName::ByRef(element), TypeStyle::C) => format!("{}*", element.format(style)),
+      (TypeName::ByRef(element), TypeStyle::FullyQualified) => format!("{}&", element.format(style)),
+      (TypeName::Other(name), TypeStyle::C) => mangle(name),
+      (TypeName::Other(name), _) => name.clone()
+    }
+  }
+
+  pub fn is_primitive(&self, code: u8) -> bool {
+    *self == TypeName::Primitive(code)
+  }
+
+  // Name of the class or generic definition, generic definitions are named with their arity, e.g. List`1 for List<int>,
+  // and primitives like object by the class behind them, e.g. System.Object
+  pub fn definition_name(&self) -> Option<String> {
+    match self {
+      TypeName::Primitive(_) => Some(self.format(TypeStyle::FullyQualified)),
+      TypeName::Class { name, .. } | TypeName::GenericInstance { name, .. } => Some(name.clone()),
+      _ => None
+    }
+  }
+
+  // Full names of the classes this type is made of, including generic arguments and element types,
+  // each one is the definition_name of that part
+  pub fn class_names(&self) -> Vec<&str> {
+    match self {
+      TypeName::Class { name, .. } => vec![name.as_str()],
+      TypeName::GenericInstance { name, arguments, .. } => {
+        let mut names = vec![name.as_str()];
+        names.extend(arguments.iter().flat_map(TypeName::class_names));
+        names
+      },
+      TypeName::Array { element, .. } | TypeName::Pointer(element) | TypeName::ByRef(element) => element.class_names(),
+      _ => vec![]
+    }
+  }
+}
+
+impl fmt::Display for TypeName {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.format(TypeStyle::CSharp))
+  }
+}
+
+// Generic definitions are named with their arity, e.g. List`1, which isn't part of the type's name in source
+pub fn strip_arity(name: &str) -> String {
+  let segments: Vec<&str> = name.split('.')
+    .map(|segment| segment.split('`').next().unwrap_or(segment))
+    .collect();
+
+  segments.join(".")
+}
+
+// Hands every segment of a definition name as many arguments as its arity says, so the arguments of a
+// nested type's declaring type end up on the declaring type, e.g. Dictionary<TKey, TValue>.Enumerator
+fn with_arguments(name: &str, arguments: &[String]) -> String {
+  if !name.contains('`') {
+    return format!("{}<{}>", name, arguments.join(", "));
+  }
+
+  let segments: Vec<String> = nested_arguments(name, arguments).into_iter().map(|(segment, taken)| match segment.split_once('`') {
+    Some((base, _)) => format!("{}<{}>", base, taken.join(", ")),
+    None => segment.to_string()
+  }).collect();
+
+  segments.join(".")
+}
+
+// Segments of a generic definition name with the arguments each one takes, Outer`1.Inner`1 with [A, B] is
+// Outer`1 with [A] and Inner`1 with [B], segments without an arity take none
+pub fn nested_arguments<'a, T>(name: &'a str, arguments: &'a [T]) -> Vec<(&'a str, &'a [T])> {
+  let mut start = 0;
+
+  name.split('.').map(|segment| {
+    let arity: usize = segment.split_once('`').and_then(|(_, arity)| arity.parse().ok()).unwrap_or(0);
+    let end = (start + arity).min(arguments.len());
+    let taken = &arguments[start..end];
+    start = end;
+
+    (segment, taken)
+  }).collect()
+}
+
+fn c_struct(name: &str, valuetype: bool) -> String {
+  format!("{}_o{}", mangle(name), if valuetype { "" } else { "*" })
+}
+
+// Turns a name into a valid C identifier
+pub fn mangle(name: &str) -> String {
+  name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
@@ -0,0 +1,230 @@
+use std::{collections::{HashMap, HashSet}, ffi::c_void, ptr::null};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError}, metadata, types::*};
+use super::{class::class_full_name, generic::GenericInstances, image::Image, type_name::TypeName, value::read_string};
+
+pub enum UsageKind {
+  TypeInfo(TypeName),
+  Il2CppType(TypeName),
+  MethodDef(*const MethodInfo, String),
+  MethodRef(*const MethodInfo, String),
+  FieldInfo(String),
+  StringLiteral(String)
+}
+
+impl UsageKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      UsageKind::TypeInfo(_) => "TypeInfo",
+      UsageKind::Il2CppType(_) => "Il2CppType",
+      UsageKind::MethodDef(..) => "MethodDef",
+      UsageKind::MethodRef(..) => "MethodRef",
+      UsageKind::FieldInfo(_) => "FieldInfo",
+      UsageKind::StringLiteral(_) => "StringLiteral"
+    }
+  }
+
+  pub fn name(&self) -> String {
+    match self {
+      UsageKind::TypeInfo(type_name) | UsageKind::Il2CppType(type_name) => type_name.to_string(),
+      UsageKind::MethodDef(_, name) | UsageKind::MethodRef(_, name) | UsageKind::FieldInfo(name) | UsageKind::StringLiteral(name) => name.clone()
+    }
+  }
+}
+
+// A metadata usage slot in GameAssembly's data section, together with what the runtime stored in it
+pub struct MetadataUsage {
+  // Position in the registration's slot table, not the index of the literal or type in the metadata
+  pub slot: usize,
+  // Index of the literal, type or method in the metadata, decoded from the slot's value in the file on disk
+  pub index: Option<u32>,
+  pub rva: usize,
+  pub kind: UsageKind
+}
+
+// A string literal slot the game hasn't loaded yet, which literal it's going to hold is all that's known
+pub struct PendingLiteral {
+  pub slot: usize,
+  pub index: u32,
+  pub rva: usize
+}
+
+// Everything a slot can point at that's reachable through the API, keyed by its address
+struct KnownHandles {
+  images: HashSet<*const c_void>,
+  classes: HashSet<*const c_void>,
+  types: HashMap<*const c_void, *const Il2CppType>,
+  methods: HashMap<*const c_void, String>,
+  fields: HashMap<*const c_void, String>,
+  string_class: *const c_void
+}
+
+impl KnownHandles {
+  fn collect(il2cpp: &Il2CppApi) -> Result<KnownHandles, Il2CppError> {
+    let mut known = KnownHandles {
+      images: HashSet::new(),
+      classes: HashSet::new(),
+      types: HashMap::new(),
+      methods: HashMap::new(),
+      fields: HashMap::new(),
+      string_class: null()
+    };
+
+    for image in Image::collect_all(il2cpp)? {
+      known.images.insert(image.handle as *const c_void);
+
+      for class in image.classes(il2cpp)? {
+        let class_name = class_full_name(il2cpp, class)?;
+
+        if class_name == "System.String" {
+          known.string_class = class as *const c_void;
+        }
+
+        known.classes.insert(class as *const c_void);
+
+        let class_type = il2cpp.class_get_type(class)?;
+        known.types.insert(class_type as *const c_void, class_type);
+
+        let method_iter: *const c_void = null();
+
+        while let Some(method) = il2cpp.class_get_methods(class, &method_iter)? {
+          known.methods.insert(method as *const c_void, format!("{}::{}", class_name, il2cpp.method_get_name(method)?));
+        }
+
+        let field_iter: *const c_void = null();
+
+        while let Some(field) = il2cpp.class_get_fields(class, &field_iter)? {
+          known.fields.insert(field as *const c_void, format!("{}.{}", class_name, il2cpp.field_get_name(field)?));
+        }
+      }
+    }
+
+    Ok(known)
+  }
+}
+
+impl MetadataUsage {
+  // Slots are only filled once a method using them ran, so anything the game hasn't touched yet is left out
+  pub fn collect_all(il2cpp: &Il2CppApi, instances: &GenericInstances) -> Result<Vec<MetadataUsage>, Il2CppError> {
+    let known = KnownHandles::collect(il2cpp)?;
+
+    let instances: HashMap<*const c_void, String> = instances.values()
+      .flatten()
+      .map(|instance| (instance.handle as *const c_void, instance.name.clone()))
+      .collect();
+
+    let mut usages = vec![];
+
+    for (position, slot, initial) in slots(il2cpp)? {
+      let value = unsafe { *slot };
+
+      // Unresolved slots hold zero or an encoded index, neither of which is a valid pointer
+      if value.is_null() || value as usize & 1 != 0 {
+        continue;
+      }
+
+      let kind = match classify(il2cpp, &known, &instances, value)? {
+        Some(kind) => kind,
+        None => continue
+      };
+
+      usages.push(MetadataUsage {
+        slot: position,
+        index: metadata::decode_usage(initial).map(|(_, index)| index),
+        rva: slot as usize - il2cpp.game_assembly.handle as usize,
+        kind
+      });
+    }
+
+    Ok(usages)
+  }
+}
+
+impl PendingLiteral {
+  // The slots collect_all leaves out because the game hasn't loaded their string yet
+  pub fn collect_all(il2cpp: &Il2CppApi) -> Result<Vec<PendingLiteral>, Il2CppError> {
+    let mut literals = vec![];
+
+    for (position, slot, initial) in slots(il2cpp)? {
+      let value = unsafe { *slot } as usize;
+
+      if value != 0 && value & 1 == 0 {
+        continue;
+      }
+
+      if let Some((metadata::USAGE_STRING_LITERAL, index)) = metadata::decode_usage(value).or(metadata::decode_usage(initial)) {
+        literals.push(PendingLiteral {
+          slot: position,
+          index,
+          rva: slot as usize - il2cpp.game_assembly.handle as usize
+        });
+      }
+    }
+
+    Ok(literals)
+  }
+}
+
+// Every slot inside GameAssembly with its position in the table and the value the file on disk has for it,
+// which is the encoded index even after the runtime replaced it, or 0 when the file can't be read
+fn slots(il2cpp: &Il2CppApi) -> Result<Vec<(usize, *mut *const c_void, usize)>, Il2CppError> {
+  let registration = il2cpp.metadata_registration()?;
+  let module = &il2cpp.game_assembly;
+
+  let slots: Vec<(usize, *mut *const c_void)> = metadata::metadata_usage_slots(registration).iter()
+    .copied()
+    .enumerate()
+    .filter(|&(_, slot)| module.contains(slot as usize))
+    .collect();
+
+  let rvas: Vec<usize> = slots.iter().map(|&(_, slot)| slot as usize - module.handle as usize).collect();
+
+  let initial = module.file_values(&rvas).unwrap_or_else(|error| {
+    println!("failed to read the metadata usage slots from {}: {}", module.path.display(), error);
+    vec![0; rvas.len()]
+  });
+
+  Ok(slots.into_iter().zip(initial).map(|((position, slot), initial)| (position, slot, initial)).collect())
+}
+
+// Methods and objects both start with their class, so methods have to be ruled out before strings
+fn classify(il2cpp: &Il2CppApi, known: &KnownHandles, instances: &HashMap<*const c_void, String>, value: *const c_void) -> Result<Option<UsageKind>, Il2CppError> {
+  let kind = if known.classes.contains(&value) {
+    UsageKind::TypeInfo(TypeName::from_class(il2cpp, value as *const Il2CppClass)?)
+  }
+  else if let Some(&type_) = known.types.get(&value) {
+    UsageKind::Il2CppType(TypeName::from_type(il2cpp, type_)?)
+  }
+  else if let Some(name) = known.methods.get(&value) {
+    UsageKind::MethodDef(value as *const MethodInfo, name.clone())
+  }
+  else if let Some(name) = known.fields.get(&value) {
+    UsageKind::FieldInfo(name.clone())
+  }
+  else if let Some(name) = instances.get(&value) {
+    UsageKind::MethodRef(value as *const MethodInfo, name.clone())
+  }
+  else {
+    let first = unsafe { *(value as *const *const c_void) };
+
+    if !known.string_class.is_null() && first == known.string_class {
+      UsageKind::StringLiteral(read_string(unsafe { &*(value as *const Il2CppString) }))
+    }
+    // Classes the API doesn't list (generic instances, arrays) still start with a pointer to their image
+    else if known.images.contains(&first) {
+      UsageKind::TypeInfo(TypeName::from_class(il2cpp, value as *const Il2CppClass)?)
+    }
+    // Methods inflated at runtime that aren't among the registration's method specs
+    else if known.classes.contains(&first) {
+      let method = value as *const MethodInfo;
+      let name = format!("{}::{}", TypeName::from_class(il2cpp, first as *const Il2CppClass)?, il2cpp.method_get_name(method)?);
+
+      UsageKind::MethodRef(method, name)
+    }
+    else {
+      return Ok(None);
+    }
+  };
+
+  Ok(Some(kind))
+}
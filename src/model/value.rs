@@ -0,0 +1,278 @@
+use std::{ffi::c_void, fmt, mem::size_of, ptr::null};
+use serde_json::{json, Value as JsonValue};
+
+use crate::il2cpp::{api::{Il2CppApi, Il2CppError, PRIMITIVE_TYPES}, constants::*, types::*};
+use super::{class::class_full_name, reflection, type_name::TypeName};
+
+// Fills the buffer with the raw value, the size is only a hint for how many bytes are needed
+type ReadCallback<'a> = &'a dyn Fn(&mut [u8; 8], usize) -> Result<(), Il2CppError>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+  Null,
+  Bool(bool),
+  Char(u16),
+  Int(i64),
+  UInt(u64),
+  Float(f32),
+  Double(f64),
+  String(String),
+  Enum(String, Box<Value>),
+  Default
+}
+
+impl Value {
+  // Reads the value of a literal field, returns None for types that can't be represented
+  pub fn read(il2cpp: &Il2CppApi, field: *const FieldInfo, field_type: *const Il2CppType, type_name: &str) -> Result<Option<Value>, Il2CppError> {
+    Value::read_with(il2cpp, field_type, type_name, &|buffer, _| il2cpp.field_static_get_value(field, buffer.as_mut_ptr() as *mut c_void))
+  }
+
+  pub fn read_primitive(il2cpp: &Il2CppApi, field: *const FieldInfo, type_name: &str) -> Result<Option<Value>, Il2CppError> {
+    let mut buffer = [0u8; 8];
+    il2cpp.field_static_get_value(field, buffer.as_mut_ptr() as *mut c_void)?;
+    Ok(Value::from_bytes(type_name, buffer))
+  }
+
+  // Reads an instance field straight out of an object, e.g. an attribute that was constructed by the runtime
+  pub fn read_instance(il2cpp: &Il2CppApi, object: *const Il2CppObject, field: *const FieldInfo, field_type: *const Il2CppType, type_name: &str) -> Result<Option<Value>, Il2CppError> {
+    let address = object as usize + il2cpp.field_get_offset(field)?;
+
+    Value::read_with(il2cpp, field_type, type_name, &|buffer, size| {
+      unsafe { std::ptr::copy_nonoverlapping(address as *const u8, buffer.as_mut_ptr(), size.min(buffer.len())) };
+      Ok(())
+    })
+  }
+
+  fn read_with(il2cpp: &Il2CppApi, field_type: *const Il2CppType, type_name: &str, read: ReadCallback) -> Result<Option<Value>, Il2CppError> {
+    let mut buffer = [0u8; 8];
+
+    if let Some(size) = primitive_size(type_name) {
+      read(&mut buffer, size)?;
+      return Ok(Value::from_bytes(type_name, buffer));
+    }
+
+    let class = il2cpp.class_from_type(field_type)?;
+
+    // Enum values are stored as their underlying type
+    if il2cpp.class_is_enum(class)? {
+      let underlying_type = match enum_underlying_type(il2cpp, class)? {
+        Some(underlying_type) => underlying_type,
+        None => return Ok(None)
+      };
+
+      let size = match primitive_size(&underlying_type) {
+        Some(size) => size,
+        None => return Ok(None)
+      };
+
+      read(&mut buffer, size)?;
+      let value = Value::from_bytes(&underlying_type, buffer);
+      return Ok(value.map(|value| Value::Enum(type_name.to_string(), Box::new(value))));
+    }
+
+    if il2cpp.class_is_valuetype(class)? {
+      return Ok(None);
+    }
+
+    // Any other reference type can only be represented when it's null
+    read(&mut buffer, size_of::<usize>())?;
+    Ok(if usize::from_le_bytes(buffer) == 0 { Some(Value::Null) } else { None })
+  }
+
+  // Reads a value boxed by the runtime, e.g. one returned from a managed method, None being a null reference
+  pub fn read_boxed(il2cpp: &Il2CppApi, object: Option<&Il2CppObject>) -> Result<Option<Value>, Il2CppError> {
+    let object = match object {
+      Some(object) => object,
+      None => return Ok(Some(Value::Null))
+    };
+
+    let class = object.klass;
+    let class_name = class_full_name(il2cpp, class)?;
+
+    let type_name = PRIMITIVE_TYPES.iter()
+      .find(|(name, _)| *name == class_name)
+      .map(|(_, alias)| alias.to_string())
+      .unwrap_or(class_name);
+
+    if type_name == "string" {
+      return Ok(Some(Value::String(read_string(unsafe { &*(object as *const Il2CppObject as *const Il2CppString) }))));
+    }
+
+    // Reflection reports parameters without a default value with these placeholders
+    if type_name == "System.DBNull" || type_name == "System.Reflection.Missing" {
+      return Ok(None);
+    }
+
+    let data = object as *const Il2CppObject as usize + size_of::<Il2CppObject>();
+    let mut buffer = [0u8; 8];
+
+    if let Some(size) = primitive_size(&type_name) {
+      unsafe { std::ptr::copy_nonoverlapping(data as *const u8, buffer.as_mut_ptr(), size) };
+      return Ok(Value::from_bytes(&type_name, buffer));
+    }
+
+    if il2cpp.class_is_enum(class)? {
+      let underlying_type = match enum_underlying_type(il2cpp, class)? {
+        Some(underlying_type) => underlying_type,
+        None => return Ok(None)
+      };
+
+      let size = match primitive_size(&underlying_type) {
+        Some(size) => size,
+        None => return Ok(None)
+      };
+
+      unsafe { std::ptr::copy_nonoverlapping(data as *const u8, buffer.as_mut_ptr(), size) };
+      let value = Value::from_bytes(&underlying_type, buffer);
+      return Ok(value.map(|value| Value::Enum(type_name, Box::new(value))));
+    }
+
+    // C# only allows default(T) for any other value type
+    Ok(if il2cpp.class_is_valuetype(class)? { Some(Value::Default) } else { None })
+  }
+
+  pub fn from_bytes(type_name: &str, buffer: [u8; 8]) -> Option<Value> {
+    let value = match type_name {
+      "bool" => Value::Bool(buffer[0] != 0),
+      "char" => Value::Char(u16::from_le_bytes([buffer[0], buffer[1]])),
+      "sbyte" => Value::Int(i8::from_le_bytes([buffer[0]]) as i64),
+      "byte" => Value::UInt(buffer[0] as u64),
+      "short" => Value::Int(i16::from_le_bytes([buffer[0], buffer[1]]) as i64),
+      "ushort" => Value::UInt(u16::from_le_bytes([buffer[0], buffer[1]]) as u64),
+      "int" => Value::Int(i32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as i64),
+      "uint" => Value::UInt(u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as u64),
+      "long" => Value::Int(i64::from_le_bytes(buffer)),
+      "ulong" => Value::UInt(u64::from_le_bytes(buffer)),
+      "float" => Value::Float(f32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]])),
+      "double" => Value::Double(f64::from_le_bytes(buffer)),
+      "string" => {
+        let string = usize::from_le_bytes(buffer) as *const Il2CppString;
+
+        if string.is_null() {
+          Value::Null
+        }
+        else {
+          Value::String(read_string(unsafe { &*string }))
+        }
+      },
+      _ => return None
+    };
+
+    Some(value)
+  }
+
+  pub fn to_json(&self) -> JsonValue {
+    match self {
+      Value::Null => JsonValue::Null,
+      Value::Bool(value) => json!(value),
+      Value::Char(value) => json!(String::from_utf16_lossy(&[*value])),
+      Value::Int(value) => json!(value),
+      Value::UInt(value) => json!(value),
+      Value::Float(value) => json!(value),
+      Value::Double(value) => json!(value),
+      Value::String(value) => json!(value),
+      Value::Enum(_, value) => value.to_json(),
+      Value::Default => json!("default")
+    }
+  }
+}
+
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Value::Null => write!(f, "null"),
+      Value::Bool(value) => write!(f, "{}", value),
+      Value::Char(value) => write!(f, "'{}'", escape(&String::from_utf16_lossy(&[*value]), '\'')),
+      Value::Int(value) => write!(f, "{}", value),
+      Value::UInt(value) => write!(f, "{}", value),
+      Value::Float(value) if value.is_nan() => write!(f, "float.NaN"),
+      Value::Float(value) if value.is_infinite() => write!(f, "float.{}Infinity", if *value > 0.0 { "Positive" } else { "Negative" }),
+      Value::Float(value) => write!(f, "{}f", value),
+      Value::Double(value) if value.is_nan() => write!(f, "double.NaN"),
+      Value::Double(value) if value.is_infinite() => write!(f, "double.{}Infinity", if *value > 0.0 { "Positive" } else { "Negative" }),
+      Value::Double(value) => write!(f, "{:?}", value),
+      Value::String(value) => write!(f, "\"{}\"", escape(value, '"')),
+      // A cast of a negative number has to be parenthesized, (Foo)-1 parses as a subtraction
+      Value::Enum(type_name, value) if matches!(**value, Value::Int(number) if number < 0) => write!(f, "({})({})", type_name, value),
+      Value::Enum(type_name, value) => write!(f, "({}){}", type_name, value),
+      Value::Default => write!(f, "default")
+    }
+  }
+}
+
+fn primitive_size(type_name: &str) -> Option<usize> {
+  match type_name {
+    "bool" | "sbyte" | "byte" => Some(1),
+    "char" | "short" | "ushort" => Some(2),
+    "int" | "uint" | "float" => Some(4),
+    "long" | "ulong" | "double" | "string" => Some(8),
+    _ => None
+  }
+}
+
+pub fn read_string(string: &Il2CppString) -> String {
+  let length = string.length.max(0) as usize;
+  let chars = unsafe { std::slice::from_raw_parts(string.chars.as_ptr(), length) };
+  String::from_utf16_lossy(chars)
+}
+
+fn escape(value: &str, quote: char) -> String {
+  let mut output = String::new();
+
+  for c in value.chars() {
+    match c {
+      '\\' => output.push_str("\\\\"),
+      '\n' => output.push_str("\\n"),
+      '\r' => output.push_str("\\r"),
+      '\t' => output.push_str("\\t"),
+      '\0' => output.push_str("\\0"),
+      c if c == quote => {
+        output.push('\\');
+        output.push(c);
+      },
+      c if c.is_control() => output.push_str(format!("\\u{:04x}", c as u32).as_str()),
+      c => output.push(c)
+    }
+  }
+
+  output
+}
+
+// Default parameter values are only exposed through reflection, so they're read by calling
+// MethodBase.GetParameters() and ParameterInfo.DefaultValue on the method's reflection object
+pub fn read_param_defaults(il2cpp: &Il2CppApi, method: *const MethodInfo) -> Result<Vec<Option<Value>>, Il2CppError> {
+  let object = il2cpp.method_get_object(method)?;
+  let mut defaults = vec![];
+
+  for param in reflection::invoke_array(il2cpp, unsafe { &*object }, "GetParameters")? {
+    if param.is_null() {
+      defaults.push(None);
+      continue;
+    }
+
+    let value = reflection::invoke(il2cpp, unsafe { &*param }, "get_DefaultValue")?;
+    defaults.push(Value::read_boxed(il2cpp, unsafe { value.as_ref() })?);
+  }
+
+  Ok(defaults)
+}
+
+// Fields that have a default value without being constants keep it in metadata, where only reflection can reach it
+pub fn read_field_default(il2cpp: &Il2CppApi, object: &Il2CppObject) -> Result<Option<Value>, Il2CppError> {
+  let value = reflection::invoke(il2cpp, object, "GetRawConstantValue")?;
+  Value::read_boxed(il2cpp, unsafe { value.as_ref() })
+}
+
+// The type that backs an enum, taken from the instance field every enum has
+pub fn enum_underlying_type(il2cpp: &Il2CppApi, class: *const Il2CppClass) -> Result<Option<String>, Il2CppError> {
+  let field_iter: *const c_void = null();
+
+  while let Some(field) = il2cpp.class_get_fields(class, &field_iter)? {
+    if il2cpp.field_get_flags(field)? & FIELD_ATTRIBUTE_STATIC == 0 {
+      let field_type = il2cpp.field_get_type(field)?;
+      return Ok(Some(TypeName::from_type(il2cpp, field_type)?.to_string()));
+    }
+  }
+
+  Ok(None)
+}
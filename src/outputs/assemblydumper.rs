@@ -0,0 +1,771 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use crate::{emit::{heaps::compress, metadata::Metadata, pe, tables::*}, il2cpp::{constants::*, types::MethodInfo}, model::{attribute::Attribute, class::{self, Class, CodeKind}, game::{Game, ImageClasses}, generic::GenericParam, image::Image, type_name::{TypeName, TypeStyle}, value::Value}};
+
+// Tiny method bodies, ldnull; throw for everything and ret for the constructors of the attributes below,
+// the first one is padded so both start on a 4 byte boundary
+const THROW_BODY: &[u8] = &[0x0a, 0x14, 0x7a, 0x00];
+const RET_BODY: &[u8] = &[0x06, 0x2a];
+
+const THROW_RVA: u32 = pe::BODIES_RVA;
+const RET_RVA: u32 = pe::BODIES_RVA + THROW_BODY.len() as u32;
+
+// Attributes defined in every assembly to carry what can't be expressed in metadata, named like Il2CppDumper's
+const HELPER_NAMESPACE: &str = "Il2CppDummyDll";
+const HELPERS: &[(&str, &[&str])] = &[
+  ("AddressAttribute", &["RVA", "VA", "Module"]),
+  ("FieldOffsetAttribute", &["Offset"]),
+  ("AttributeAttribute", &["Name", "ConstructorRVA"])
+];
+
+const ADDRESS_ATTRIBUTE: usize = 0;
+const FIELD_OFFSET_ATTRIBUTE: usize = 1;
+const ATTRIBUTE_ATTRIBUTE: usize = 2;
+
+const TYPE_FLAGS_HELPER: u32 = 0x00100101;
+const FIELD_FLAGS_PUBLIC: u16 = 0x0006;
+const METHOD_FLAGS_CONSTRUCTOR: u16 = 0x1886;
+const METHOD_IMPL_INTERNAL_CALL: u16 = 0x1000;
+
+const SEMANTICS_SETTER: u16 = 0x01;
+const SEMANTICS_GETTER: u16 = 0x02;
+const SEMANTICS_ADD_ON: u16 = 0x08;
+const SEMANTICS_REMOVE_ON: u16 = 0x10;
+const SEMANTICS_FIRE: u16 = 0x20;
+
+// Signature calling conventions and element types that aren't il2cpp type codes too, ECMA-335 II.23.1.16
+const SIGNATURE_FIELD: u8 = 0x06;
+const SIGNATURE_PROPERTY: u8 = 0x08;
+const SIGNATURE_GENERIC: u8 = 0x10;
+const SIGNATURE_HAS_THIS: u8 = 0x20;
+const ELEMENT_TYPE_STRING_SERIALIZED: u8 = 0x0e;
+const CUSTOM_ATTRIBUTE_FIELD: u8 = 0x53;
+
+struct TypeInfo {
+  assembly: String,
+  full_name: String,
+  namespace: String,
+  name: String,
+  declaring_type: Option<String>,
+  // Type code of an enum's underlying type, what its constants are stored as
+  underlying_type: Option<u8>
+}
+
+// Every class of every image, so types defined in other assemblies can be referenced
+struct TypeIndex {
+  types: Vec<TypeInfo>,
+  // Positions in types by full name, in image order since a name can be defined in several images
+  by_name: HashMap<String, Vec<usize>>
+}
+
+impl TypeIndex {
+  fn collect(game: &Game) -> TypeIndex {
+    let mut index = TypeIndex {
+      types: vec![],
+      by_name: HashMap::new()
+    };
+
+    for (image, class) in game.all_classes() {
+      let full_name = class.full_name();
+
+      index.by_name.entry(full_name.clone()).or_default().push(index.types.len());
+
+      // Nested types keep the namespace of their declaring type in the model, but have none in metadata
+      index.types.push(TypeInfo {
+        assembly: assembly_name(&image.name),
+        full_name,
+        namespace: if class.declaring_type.is_some() { String::new() } else { class.namespace.clone() },
+        name: class.name.clone(),
+        declaring_type: class.declaring_type.clone(),
+        underlying_type: match class.enum_underlying_type() {
+          Some(TypeName::Primitive(code)) => Some(*code),
+          _ => None
+        }
+      });
+    }
+
+    index
+  }
+
+  // The definition in the given assembly when it has one, otherwise the one in the first image defining the name
+  fn resolve(&self, assembly: &str, full_name: &str) -> Option<&TypeInfo> {
+    let positions = self.by_name.get(full_name)?;
+
+    positions.iter()
+      .map(|&position| &self.types[position])
+      .find(|info| info.assembly == assembly)
+      .or_else(|| positions.first().map(|&position| &self.types[position]))
+  }
+}
+
+// Generic parameters in scope, VAR and MVAR refer to them by position
+#[derive(Clone, Copy)]
+struct Context<'a> {
+  class: &'a [GenericParam],
+  method: &'a [GenericParam]
+}
+
+struct GenericParamRow {
+  owner: u32,
+  number: u16,
+  flags: u16,
+  name: u32,
+  constraints: Vec<u32>
+}
+
+struct AssemblyWriter<'a> {
+  index: &'a TypeIndex,
+  image_base: usize,
+  assembly: String,
+  metadata: Metadata,
+  type_defs: HashMap<String, u32>,
+  type_refs: HashMap<(String, String), u32>,
+  type_specs: HashMap<Vec<u8>, u32>,
+  assembly_refs: HashMap<String, u32>,
+  generic_params: Vec<GenericParamRow>,
+  helper_constructors: Vec<u32>
+}
+
+fn assembly_name(image_name: &str) -> String {
+  image_name.strip_suffix(".dll").unwrap_or(image_name).to_string()
+}
+
+fn ser_string(value: &str, output: &mut Vec<u8>) {
+  compress(value.len() as u32, output);
+  output.extend_from_slice(value.as_bytes());
+}
+
+// Module version id, derived from the name so dumping the same assembly twice gives the same file
+fn mvid(name: &str) -> [u8; 16] {
+  let mut hash: u128 = 0x6c62272e07bb014262b821756295c58d;
+
+  for byte in name.bytes() {
+    hash ^= byte as u128;
+    hash = hash.wrapping_mul(0x0000000001000000000000000000013b);
+  }
+
+  hash.to_le_bytes()
+}
+
+// Value blob of a literal field or default parameter, None for values that can't be stored as constants
+fn encode_constant(value: &Value, type_code: Option<u8>) -> Option<(u8, Vec<u8>)> {
+  let type_code = match (value, type_code) {
+    (Value::Null, _) => return Some((IL2CPP_TYPE_CLASS, vec![0; 4])),
+    (Value::Enum(_, value), _) => return encode_constant(value, type_code),
+    (_, Some(type_code)) => type_code,
+    _ => return None
+  };
+
+  let integer_size = match type_code {
+    IL2CPP_TYPE_I1 | IL2CPP_TYPE_U1 => 1,
+    IL2CPP_TYPE_I2 | IL2CPP_TYPE_U2 => 2,
+    IL2CPP_TYPE_I4 | IL2CPP_TYPE_U4 => 4,
+    _ => 8
+  };
+
+  let bytes = match (value, type_code) {
+    (Value::Bool(value), IL2CPP_TYPE_BOOLEAN) => vec![*value as u8],
+    (Value::Char(value), IL2CPP_TYPE_CHAR) => value.to_le_bytes().to_vec(),
+    (Value::Int(value), IL2CPP_TYPE_I1..=IL2CPP_TYPE_U8) => value.to_le_bytes()[..integer_size].to_vec(),
+    (Value::UInt(value), IL2CPP_TYPE_I1..=IL2CPP_TYPE_U8) => value.to_le_bytes()[..integer_size].to_vec(),
+    (Value::Float(value), IL2CPP_TYPE_R4) => value.to_le_bytes().to_vec(),
+    (Value::Double(value), IL2CPP_TYPE_R8) => value.to_le_bytes().to_vec(),
+    (Value::String(value), IL2CPP_TYPE_STRING) => value.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+    _ => return None
+  };
+
+  Some((type_code, bytes))
+}
+
+impl AssemblyWriter<'_> {
+  fn assembly_ref(&mut self, name: &str) -> u32 {
+    if let Some(&row) = self.assembly_refs.get(name) {
+      return row;
+    }
+
+    let name_index = self.metadata.strings.add(name);
+
+    let row = self.metadata.tables.push(ASSEMBLY_REF, vec![
+      Column::U16(0), Column::U16(0), Column::U16(0), Column::U16(0),
+      Column::U32(0),
+      Column::Blob(0),
+      Column::String(name_index),
+      Column::String(0),
+      Column::Blob(0)
+    ]);
+
+    self.assembly_refs.insert(name.to_string(), row);
+    row
+  }
+
+  fn type_ref(&mut self, full_name: &str) -> Option<u32> {
+    let index = self.index;
+    let info = index.resolve(&self.assembly, full_name)?;
+    self.type_ref_to(info)
+  }
+
+  // Keyed by assembly and name, the same name can end up referenced in more than one assembly
+  fn type_ref_to(&mut self, info: &TypeInfo) -> Option<u32> {
+    let key = (info.assembly.clone(), info.full_name.clone());
+
+    if let Some(&row) = self.type_refs.get(&key) {
+      return Some(row);
+    }
+
+    // Nested types are scoped to their declaring type in the same assembly and have no namespace of their own
+    let (scope, namespace) = match &info.declaring_type {
+      Some(declaring_type) => {
+        let index = self.index;
+        let declaring_info = index.resolve(&info.assembly, declaring_type)?;
+        (Coded::ResolutionScope.encode(TYPE_REF, self.type_ref_to(declaring_info)?), "")
+      },
+      None if info.assembly == self.assembly => (Coded::ResolutionScope.encode(MODULE, 1), info.namespace.as_str()),
+      None => (Coded::ResolutionScope.encode(ASSEMBLY_REF, self.assembly_ref(&info.assembly)), info.namespace.as_str())
+    };
+
+    let name = self.metadata.strings.add(&info.name);
+    let namespace = self.metadata.strings.add(namespace);
+
+    let row = self.metadata.tables.push(TYPE_REF, vec![
+      Column::Coded(Coded::ResolutionScope, scope),
+      Column::String(name),
+      Column::String(namespace)
+    ]);
+
+    self.type_refs.insert(key, row);
+    Some(row)
+  }
+
+  // TypeDefOrRef coded index of a type known by name
+  fn type_def_or_ref(&mut self, full_name: &str) -> Option<u32> {
+    match self.type_defs.get(full_name) {
+      Some(&row) => Some(Coded::TypeDefOrRef.encode(TYPE_DEF, row)),
+      None => self.type_ref(full_name).map(|row| Coded::TypeDefOrRef.encode(TYPE_REF, row))
+    }
+  }
+
+  // Types without a definition of their own (generic instances, arrays) are referenced through a TypeSpec
+  fn type_token(&mut self, type_name: &TypeName, context: Context) -> Option<u32> {
+    match type_name {
+      TypeName::Class { name, .. } => self.type_def_or_ref(name),
+      TypeName::Primitive(_) => self.type_def_or_ref(&type_name.format(TypeStyle::FullyQualified)),
+      _ => {
+        let mut signature = vec![];
+        self.encode_type(type_name, context, &mut signature);
+
+        let row = match self.type_specs.get(&signature) {
+          Some(&row) => row,
+          None => {
+            let blob = self.metadata.blobs.add(&signature);
+            let row = self.metadata.tables.push(TYPE_SPEC, vec![Column::Blob(blob)]);
+
+            self.type_specs.insert(signature, row);
+            row
+          }
+        };
+
+        Some(Coded::TypeDefOrRef.encode(TYPE_SPEC, row))
+      }
+    }
+  }
+
+  // Type in the signature encoding, ECMA-335 II.23.2.12, anything that can't be resolved becomes object
+  fn encode_type(&mut self, type_name: &TypeName, context: Context, output: &mut Vec<u8>) {
+    match type_name {
+      // il2cpp type codes are the element types of the signature encoding
+      TypeName::Primitive(code) => output.push(*code),
+      TypeName::Class { name, valuetype } => match self.type_def_or_ref(name) {
+        Some(token) => {
+          output.push(if *valuetype { IL2CPP_TYPE_VALUETYPE } else { IL2CPP_TYPE_CLASS });
+          compress(token, output);
+        },
+        None => output.push(IL2CPP_TYPE_OBJECT)
+      },
+      TypeName::GenericInstance { name, valuetype, arguments } => {
+        // Instances are named after their definition, arities included
+        match self.type_def_or_ref(name) {
+          Some(token) => {
+            output.push(IL2CPP_TYPE_GENERICINST);
+            output.push(if *valuetype { IL2CPP_TYPE_VALUETYPE } else { IL2CPP_TYPE_CLASS });
+            compress(token, output);
+            compress(arguments.len() as u32, output);
+
+            for argument in arguments {
+              self.encode_type(argument, context, output);
+            }
+          },
+          None => output.push(IL2CPP_TYPE_OBJECT)
+        }
+      },
+      TypeName::GenericParam(name) => {
+        if let Some(position) = context.method.iter().position(|param| param.name == *name) {
+          output.push(IL2CPP_TYPE_MVAR);
+          compress(position as u32, output);
+        }
+        else if let Some(position) = context.class.iter().position(|param| param.name == *name) {
+          output.push(IL2CPP_TYPE_VAR);
+          compress(position as u32, output);
+        }
+        else {
+          output.push(IL2CPP_TYPE_OBJECT);
+        }
+      },
+      TypeName::Array { element, rank: 1 } => {
+        output.push(IL2CPP_TYPE_SZARRAY);
+        self.encode_type(element, context, output);
+      },
+      TypeName::Array { element, rank } => {
+        output.push(IL2CPP_TYPE_ARRAY);
+        self.encode_type(element, context, output);
+        compress(*rank as u32, output);
+        output.extend_from_slice(&[0, 0]);
+      },
+      TypeName::Pointer(element) => {
+        output.push(IL2CPP_TYPE_PTR);
+        self.encode_type(element, context, output);
+      },
+      TypeName::ByRef(element) => {
+        output.push(IL2CPP_TYPE_BYREF);
+        self.encode_type(element, context, output);
+      },
+      // Function pointers are kept as native ints
+      TypeName::Other(_) => output.push(IL2CPP_TYPE_I)
+    }
+  }
+
+  // Named string fields only, which is all the helper attributes have
+  fn custom_attribute(&mut self, parent: u32, helper: usize, arguments: &[(&str, String)]) {
+    let mut blob = vec![0x01, 0x00];
+    blob.extend_from_slice(&(arguments.len() as u16).to_le_bytes());
+
+    for (name, value) in arguments {
+      blob.push(CUSTOM_ATTRIBUTE_FIELD);
+      blob.push(ELEMENT_TYPE_STRING_SERIALIZED);
+      ser_string(name, &mut blob);
+      ser_string(value, &mut blob);
+    }
+
+    let blob = self.metadata.blobs.add(&blob);
+    let constructor = Coded::CustomAttributeType.encode(METHOD_DEF, self.helper_constructors[helper]);
+
+    self.metadata.tables.push(CUSTOM_ATTRIBUTE, vec![
+      Column::Coded(Coded::HasCustomAttribute, parent),
+      Column::Coded(Coded::CustomAttributeType, constructor),
+      Column::Blob(blob)
+    ]);
+  }
+
+  // The game's own attributes are kept by name, their constructors can't be referenced reliably
+  fn attributes(&mut self, parent: u32, attributes: &[Attribute]) {
+    for attribute in attributes {
+      let mut arguments = vec![("Name", attribute.type_name.clone())];
+
+      if let Some(rva) = attribute.constructor_rva {
+        arguments.push(("ConstructorRVA", format!("0x{:x}", rva)));
+      }
+
+      self.custom_attribute(parent, ATTRIBUTE_ATTRIBUTE, &arguments);
+    }
+  }
+
+  fn constant(&mut self, parent: u32, type_code: u8, value: &[u8]) {
+    let blob = self.metadata.blobs.add(value);
+
+    // The type is a single byte followed by a padding byte
+    self.metadata.tables.push(CONSTANT, vec![
+      Column::U16(type_code as u16),
+      Column::Coded(Coded::HasConstant, parent),
+      Column::Blob(blob)
+    ]);
+  }
+
+  fn add_generic_params(&mut self, owner: u32, params: &[GenericParam], context: Context) {
+    for (number, param) in params.iter().enumerate() {
+      let constraints = param.constraints.iter()
+        .filter_map(|constraint| self.type_token(constraint, context))
+        .collect();
+
+      let name = self.metadata.strings.add(&param.name);
+
+      self.generic_params.push(GenericParamRow {
+        owner,
+        number: number as u16,
+        flags: param.flags as u16,
+        name,
+        constraints
+      });
+    }
+  }
+
+  fn type_def(&mut self, flags: u32, name: &str, namespace: &str, extends: u32) -> u32 {
+    let name = self.metadata.strings.add(name);
+    let namespace = self.metadata.strings.add(namespace);
+    let field_list = self.metadata.tables.next_row(FIELD);
+    let method_list = self.metadata.tables.next_row(METHOD_DEF);
+
+    self.metadata.tables.push(TYPE_DEF, vec![
+      Column::U32(flags),
+      Column::String(name),
+      Column::String(namespace),
+      Column::Coded(Coded::TypeDefOrRef, extends),
+      Column::Index(FIELD, field_list),
+      Column::Index(METHOD_DEF, method_list)
+    ])
+  }
+
+  fn field(&mut self, flags: u16, name: &str, signature: &[u8]) -> u32 {
+    let name = self.metadata.strings.add(name);
+    let signature = self.metadata.blobs.add(signature);
+
+    self.metadata.tables.push(FIELD, vec![Column::U16(flags), Column::String(name), Column::Blob(signature)])
+  }
+
+  fn method(&mut self, rva: u32, impl_flags: u16, flags: u16, name: &str, signature: &[u8]) -> u32 {
+    let name = self.metadata.strings.add(name);
+    let signature = self.metadata.blobs.add(signature);
+    let param_list = self.metadata.tables.next_row(PARAM);
+
+    self.metadata.tables.push(METHOD_DEF, vec![
+      Column::U32(rva),
+      Column::U16(impl_flags),
+      Column::U16(flags),
+      Column::String(name),
+      Column::Blob(signature),
+      Column::Index(PARAM, param_list)
+    ])
+  }
+
+  fn write_helpers(&mut self) {
+    let extends = self.type_def_or_ref("System.Attribute").unwrap_or(0);
+
+    for (name, fields) in HELPERS {
+      self.type_def(TYPE_FLAGS_HELPER, name, HELPER_NAMESPACE, extends);
+
+      for field in fields.iter() {
+        self.field(FIELD_FLAGS_PUBLIC, field, &[SIGNATURE_FIELD, IL2CPP_TYPE_STRING]);
+      }
+
+      let constructor = self.method(RET_RVA, 0, METHOD_FLAGS_CONSTRUCTOR, ".ctor", &[SIGNATURE_HAS_THIS, 0, IL2CPP_TYPE_VOID]);
+      self.helper_constructors.push(constructor);
+    }
+  }
+
+  // first_row counts the rows before the class that aren't one of the game's types
+  fn assign_rows(&mut self, class: &Class, first_row: u32) {
+    let row = self.type_defs.len() as u32 + first_row;
+    self.type_defs.insert(class.full_name(), row);
+
+    for nested_type in &class.nested_types {
+      self.assign_rows(nested_type, first_row);
+    }
+  }
+
+  // The type code a constant of this type is stored as, enums of any image use their underlying type
+  fn constant_type(&self, type_name: &TypeName) -> Option<u8> {
+    match type_name {
+      TypeName::Primitive(code) => Some(*code),
+      TypeName::Class { name, .. } => self.index.resolve(&self.assembly, name)?.underlying_type,
+      _ => None
+    }
+  }
+
+  fn write_class(&mut self, class: &Class, inherited_params: &[GenericParam], declaring_row: Option<u32>) {
+    // Nested types share the parameters of their declaring type
+    let params = if class.generic_params.is_empty() { inherited_params } else { &class.generic_params };
+    let context = Context { class: params, method: &[] };
+
+    let extends = match &class.parent {
+      Some(parent) => self.type_token(parent, context).unwrap_or(0),
+      None => 0
+    };
+
+    let namespace = if declaring_row.is_some() { "" } else { class.namespace.as_str() };
+    let row = self.type_def(class.flags as u32, &class.name, namespace, extends);
+
+    if let Some(declaring_row) = declaring_row {
+      self.metadata.tables.push(NESTED_CLASS, vec![Column::Index(TYPE_DEF, row), Column::Index(TYPE_DEF, declaring_row)]);
+    }
+
+    self.add_generic_params(Coded::TypeOrMethodDef.encode(TYPE_DEF, row), params, context);
+
+    for interface in &class.interfaces {
+      if let Some(token) = self.type_token(interface, context) {
+        self.metadata.tables.push(INTERFACE_IMPL, vec![Column::Index(TYPE_DEF, row), Column::Coded(Coded::TypeDefOrRef, token)]);
+      }
+    }
+
+    self.attributes(Coded::HasCustomAttribute.encode(TYPE_DEF, row), &class.attributes);
+
+    for field in &class.fields {
+      let constant = field.value.as_ref().and_then(|value| encode_constant(value, self.constant_type(&field.type_name)));
+
+      let mut flags = field.flags & !(FIELD_ATTRIBUTE_HAS_DEFAULT | FIELD_ATTRIBUTE_HAS_FIELD_RVA | FIELD_ATTRIBUTE_HAS_FIELD_MARSHAL | FIELD_ATTRIBUTE_PINVOKE_IMPL);
+
+      if constant.is_some() {
+        flags |= FIELD_ATTRIBUTE_HAS_DEFAULT;
+      }
+
+      let mut signature = vec![SIGNATURE_FIELD];
+      self.encode_type(&field.type_name, context, &mut signature);
+
+      let field_row = self.field(flags as u16, &field.name, &signature);
+      let parent = Coded::HasCustomAttribute.encode(FIELD, field_row);
+
+      if let Some((type_code, value)) = constant {
+        self.constant(Coded::HasConstant.encode(FIELD, field_row), type_code, &value);
+      }
+
+      if field.flags & FIELD_ATTRIBUTE_LITERAL == 0 && !field.thread_static {
+        self.custom_attribute(parent, FIELD_OFFSET_ATTRIBUTE, &[("Offset", format!("0x{:x}", field.offset))]);
+      }
+
+      self.attributes(parent, &field.attributes);
+    }
+
+    let mut method_rows = HashMap::new();
+
+    for method in &class.methods {
+      let method_context = Context { class: params, method: &method.generic_params };
+      let is_static = method.flags & METHOD_ATTRIBUTE_STATIC != 0;
+
+      let mut calling_convention = if is_static { 0 } else { SIGNATURE_HAS_THIS };
+
+      if !method.generic_params.is_empty() {
+        calling_convention |= SIGNATURE_GENERIC;
+      }
+
+      let mut signature = vec![calling_convention];
+
+      if !method.generic_params.is_empty() {
+        compress(method.generic_params.len() as u32, &mut signature);
+      }
+
+      compress(method.params.len() as u32, &mut signature);
+      self.encode_type(&method.return_type, method_context, &mut signature);
+
+      for param in &method.params {
+        self.encode_type(&param.type_name, method_context, &mut signature);
+      }
+
+      let (rva, impl_flags) = if method.internal_call.is_some() {
+        (0, METHOD_IMPL_INTERNAL_CALL)
+      }
+      else if method.flags & METHOD_ATTRIBUTE_ABSTRACT != 0 {
+        (0, 0)
+      }
+      else {
+        (THROW_RVA, 0)
+      };
+
+      let flags = method.flags & !(METHOD_ATTRIBUTE_PINVOKE_IMPL | METHOD_ATTRIBUTE_UNMANAGED_EXPORT | METHOD_ATTRIBUTE_HAS_SECURITY | METHOD_ATTRIBUTE_REQUIRE_SEC_OBJECT);
+      let method_row = self.method(rva, impl_flags, flags as u16, &method.name, &signature);
+
+      method_rows.insert(method.handle, method_row);
+
+      for (position, param) in method.params.iter().enumerate() {
+        let constant = param.default.as_ref().and_then(|value| encode_constant(value, self.constant_type(&param.type_name)));
+
+        let mut flags = param.attrs & !(PARAM_ATTRIBUTE_HAS_DEFAULT | PARAM_ATTRIBUTE_HAS_FIELD_MARSHAL);
+
+        if constant.is_some() {
+          flags |= PARAM_ATTRIBUTE_HAS_DEFAULT;
+        }
+
+        let name = self.metadata.strings.add(&param.name);
+        let param_row = self.metadata.tables.push(PARAM, vec![Column::U16(flags as u16), Column::U16(position as u16 + 1), Column::String(name)]);
+
+        if let Some((type_code, value)) = constant {
+          self.constant(Coded::HasConstant.encode(PARAM, param_row), type_code, &value);
+        }
+
+        self.attributes(Coded::HasCustomAttribute.encode(PARAM, param_row), &param.attributes);
+      }
+
+      self.add_generic_params(Coded::TypeOrMethodDef.encode(METHOD_DEF, method_row), &method.generic_params, method_context);
+
+      let parent = Coded::HasCustomAttribute.encode(METHOD_DEF, method_row);
+
+      match (method.code.kind, &method.code.module, method.code.rva) {
+        (CodeKind::Compiled, _, Some(rva)) => {
+          self.custom_attribute(parent, ADDRESS_ATTRIBUTE, &[("RVA", format!("0x{:x}", rva)), ("VA", format!("0x{:x}", rva + self.image_base))]);
+        },
+        (CodeKind::External, Some(module), Some(rva)) => {
+          self.custom_attribute(parent, ADDRESS_ATTRIBUTE, &[("RVA", format!("0x{:x}", rva)), ("Module", module.clone())]);
+        },
+        _ => {}
+      }
+
+      self.attributes(parent, &method.attributes);
+    }
+
+    if !class.properties.is_empty() {
+      let property_list = self.metadata.tables.next_row(PROPERTY);
+      self.metadata.tables.push(PROPERTY_MAP, vec![Column::Index(TYPE_DEF, row), Column::Index(PROPERTY, property_list)]);
+    }
+
+    for property in &class.properties {
+      let accessor_flags = property.getter.as_ref().or(property.setter.as_ref()).map(|accessor| accessor.flags).unwrap_or(0);
+
+      // Indexers take the getter's parameters, or the setter's without the value
+      let accessor_params = |accessor: &Option<class::Accessor>| accessor.as_ref()
+        .and_then(|accessor| class.methods.iter().find(|method| method.handle == accessor.handle))
+        .map(|method| &method.params[..]);
+
+      let params = match (accessor_params(&property.getter), accessor_params(&property.setter)) {
+        (Some(params), _) => params,
+        (None, Some(params)) => &params[..params.len().saturating_sub(1)],
+        (None, None) => &[]
+      };
+
+      let mut signature = vec![SIGNATURE_PROPERTY | if accessor_flags & METHOD_ATTRIBUTE_STATIC == 0 { SIGNATURE_HAS_THIS } else { 0 }];
+      compress(params.len() as u32, &mut signature);
+      self.encode_type(&property.type_name, context, &mut signature);
+
+      for param in params {
+        self.encode_type(&param.type_name, context, &mut signature);
+      }
+
+      let name = self.metadata.strings.add(&property.name);
+      let signature = self.metadata.blobs.add(&signature);
+      let property_row = self.metadata.tables.push(PROPERTY, vec![Column::U16(0), Column::String(name), Column::Blob(signature)]);
+
+      let accessors = [(SEMANTICS_GETTER, &property.getter), (SEMANTICS_SETTER, &property.setter)];
+      self.semantics(Coded::HasSemantics.encode(PROPERTY, property_row), &accessors, &method_rows);
+    }
+
+    if !class.events.is_empty() {
+      let event_list = self.metadata.tables.next_row(EVENT);
+      self.metadata.tables.push(EVENT_MAP, vec![Column::Index(TYPE_DEF, row), Column::Index(EVENT, event_list)]);
+    }
+
+    for event in &class.events {
+      let event_type = self.type_token(&event.type_name, context).unwrap_or(0);
+      let name = self.metadata.strings.add(&event.name);
+      let event_row = self.metadata.tables.push(EVENT, vec![Column::U16(0), Column::String(name), Column::Coded(Coded::TypeDefOrRef, event_type)]);
+
+      let accessors = [(SEMANTICS_ADD_ON, &event.add), (SEMANTICS_REMOVE_ON, &event.remove), (SEMANTICS_FIRE, &event.raise)];
+      self.semantics(Coded::HasSemantics.encode(EVENT, event_row), &accessors, &method_rows);
+    }
+
+    for nested_type in &class.nested_types {
+      self.write_class(nested_type, params, Some(row));
+    }
+  }
+
+  // Accessors are matched by handle, by name an overloaded accessor would be bound to the first overload
+  fn semantics(&mut self, association: u32, accessors: &[(u16, &Option<class::Accessor>)], method_rows: &HashMap<*const MethodInfo, u32>) {
+    for (semantics, accessor) in accessors {
+      if let Some(&method_row) = accessor.as_ref().and_then(|accessor| method_rows.get(&accessor.handle)) {
+        self.metadata.tables.push(METHOD_SEMANTICS, vec![
+          Column::U16(*semantics),
+          Column::Index(METHOD_DEF, method_row),
+          Column::Coded(Coded::HasSemantics, association)
+        ]);
+      }
+    }
+  }
+
+  fn finish(mut self) -> Vec<u8> {
+    self.generic_params.sort_by_key(|param| (param.owner, param.number));
+
+    for param in std::mem::take(&mut self.generic_params) {
+      let param_row = self.metadata.tables.push(GENERIC_PARAM, vec![
+        Column::U16(param.number),
+        Column::U16(param.flags),
+        Column::Coded(Coded::TypeOrMethodDef, param.owner),
+        Column::String(param.name)
+      ]);
+
+      for constraint in param.constraints {
+        self.metadata.tables.push(GENERIC_PARAM_CONSTRAINT, vec![Column::Index(GENERIC_PARAM, param_row), Column::Coded(Coded::TypeDefOrRef, constraint)]);
+      }
+    }
+
+    self.metadata.tables.sort(CONSTANT, &[1]);
+    self.metadata.tables.sort(CUSTOM_ATTRIBUTE, &[0]);
+    self.metadata.tables.sort(METHOD_SEMANTICS, &[2]);
+
+    let mut bodies = THROW_BODY.to_vec();
+    bodies.extend_from_slice(RET_BODY);
+
+    pe::write(&bodies, &self.metadata.write())
+  }
+}
+
+fn write_assembly(image: &Image, classes: &[Class], index: &TypeIndex, image_base: usize) -> Vec<u8> {
+  let mut writer = AssemblyWriter {
+    index,
+    image_base,
+    assembly: assembly_name(&image.name),
+    metadata: Metadata::default(),
+    type_defs: HashMap::new(),
+    type_refs: HashMap::new(),
+    type_specs: HashMap::new(),
+    assembly_refs: HashMap::new(),
+    generic_params: vec![],
+    helper_constructors: vec![]
+  };
+
+  let module_name = writer.metadata.strings.add(&image.name);
+  let mvid = writer.metadata.guids.add(mvid(&image.name));
+  writer.metadata.tables.push(MODULE, vec![Column::U16(0), Column::String(module_name), Column::Guid(mvid), Column::Guid(0), Column::Guid(0)]);
+
+  let assembly_name = writer.metadata.strings.add(&writer.assembly);
+  writer.metadata.tables.push(ASSEMBLY, vec![
+    Column::U32(0x8004),
+    Column::U16(0), Column::U16(0), Column::U16(0), Column::U16(0),
+    Column::U32(0),
+    Column::Blob(0),
+    Column::String(assembly_name),
+    Column::String(0)
+  ]);
+
+  // The first row is the module's own type, images have one of their own that takes it, followed by the helpers
+  let module_index = classes.iter().position(|class| class.name == "<Module>" && class.namespace.is_empty());
+  let module = module_index.map(|index| &classes[index]);
+
+  let others: Vec<&Class> = classes.iter().enumerate()
+    .filter(|(index, _)| Some(*index) != module_index)
+    .map(|(_, class)| class)
+    .collect();
+
+  // Rows of the game's types have to be known up front since members refer to types defined later
+  if let Some(module) = module {
+    writer.assign_rows(module, 1);
+  }
+
+  let first_row = 1 + HELPERS.len() as u32 + if module.is_some() { 0 } else { 1 };
+
+  for class in &others {
+    writer.assign_rows(class, first_row);
+  }
+
+  match module {
+    Some(module) => writer.write_class(module, &[], None),
+    None => { writer.type_def(0, "<Module>", "", 0); }
+  }
+
+  writer.write_helpers();
+
+  for class in others {
+    writer.write_class(class, &[], None);
+  }
+
+  writer.finish()
+}
+
+// Writes a metadata-only assembly for every image to the DummyDll folder, members have no code
+// and carry their addresses in attributes, so they can be browsed in dnSpy or ILSpy and referenced from C#
+pub fn dump(game: &Game) -> Result<(), Box<dyn Error>> {
+  let index = TypeIndex::collect(game);
+
+  fs::create_dir_all("DummyDll")?;
+
+  for ImageClasses { image, classes } in &game.images {
+    fs::write(format!("DummyDll/{}", image.name), write_assembly(image, classes, &index, game.image_base))?;
+  }
+
+  println!("{} assemblies saved to the DummyDll folder", game.images.len());
+
+  Ok(())
+}
@@ -1,218 +1,487 @@
-use std::{error::Error, ffi::c_void, fs::File, io::Write, ptr::null};
+use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet}, error::Error, fs, io::Write, path::{Path, PathBuf}};
+
+use crate::{il2cpp::constants::*, model::{attribute::Attribute, class::{Accessor, Class, ClassKind, CodeKind, MethodCode, Param}, game::{Game, ImageClasses}, generic::{GenericInstance, GenericParam}, icall::InternalCall, image::Image, type_name::TypeName}, outputs::sink::{Compression, Sink}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsLayout {
+  // Everything in dump.cs
+  SingleFile,
+  // dump/Assembly-CSharp/RPG/Client.cs with every type of the namespace
+  PerNamespace,
+  // dump/Assembly-CSharp/RPG/Client/AutoScrollRect.cs, nested types stay in the file of their declaring type
+  PerClass
+}
 
-use crate::il2cpp::{api, constants::*, types::Il2CppClass};
+fn write_images(game: &Game, output: &mut impl Write) -> Result<(), Box<dyn Error>> {
+  for entry in &game.images {
+    writeln!(output, "// Image {}: {}", entry.image.index, entry.image.name)?;
+  }
 
-fn write_images() -> Result<String, Box<dyn Error>> {
-  let il2cpp = api::get_il2cpp_api()?;
-  let mut output = String::new();
+  Ok(())
+}
 
-  let domain = il2cpp.domain_get()?;
+const KEYWORDS: &[&str] = &[
+  "abstract", "as", "base", "bool", "break", "byte", "case", "catch", "char", "checked", "class", "const",
+  "continue", "decimal", "default", "delegate", "do", "double", "else", "enum", "event", "explicit", "extern",
+  "false", "finally", "fixed", "float", "for", "foreach", "goto", "if", "implicit", "in", "int", "interface",
+  "internal", "is", "lock", "long", "namespace", "new", "null", "object", "operator", "out", "override",
+  "params", "private", "protected", "public", "readonly", "ref", "return", "sbyte", "sealed", "short",
+  "sizeof", "stackalloc", "static", "string", "struct", "switch", "this", "throw", "true", "try", "typeof",
+  "uint", "ulong", "unchecked", "unsafe", "ushort", "using", "virtual", "void", "volatile", "while"
+];
+
+// Parameter names have to be valid identifiers for the signature to parse
+pub fn write_param_name(name: &str, index: usize) -> String {
+  if name.is_empty() {
+    format!("param{}", index)
+  }
+  else if KEYWORDS.contains(&name) {
+    format!("@{}", name)
+  }
+  else {
+    name.to_string()
+  }
+}
 
-  let assembly_size: usize = 0;
-  let assemblies = il2cpp.domain_get_assemblies(domain, &assembly_size)?;
+pub fn write_generic_params(params: &[GenericParam]) -> String {
+  if params.is_empty() {
+    return String::new();
+  }
 
-  for i in 0..assembly_size {
-    let assembly = unsafe { *assemblies.offset(i as isize) };
+  let params: Vec<&str> = params.iter().map(|param| param.name.as_str()).collect();
+  format!("<{}>", params.join(", "))
+}
 
-    if assembly.is_null() {
-      continue;
+fn write_generic_constraints(params: &[GenericParam]) -> String {
+  let mut output = String::new();
+
+  for param in params {
+    let constraints = param.constraint_list();
+
+    if !constraints.is_empty() {
+      let fmt = format!(" where {} : {}", param.name, constraints.join(", "));
+      output.push_str(fmt.as_str());
     }
+  }
 
-    let image = il2cpp.assembly_get_image(assembly)?;
-    let name = il2cpp.image_get_name(image)?;
+  output
+}
+
+fn write_attribute(attribute: &Attribute) -> String {
+  let arguments: Vec<String> = attribute.arguments.iter()
+    .map(|(name, value)| format!("{} = {}", name, value))
+    .collect();
+
+  let arguments = if arguments.is_empty() { String::new() } else { format!("({})", arguments.join(", ")) };
+  format!("[{}{}]", attribute.short_name(), arguments)
+}
+
+fn write_attributes(attributes: &[Attribute], indent: &str, image_base: usize) -> String {
+  let mut output = String::new();
+
+  for attribute in attributes {
+    let fmt = match attribute.constructor_rva {
+      Some(rva) => format!("{}{} // .ctor RVA: 0x{:x} VA: 0x{:x}\n", indent, write_attribute(attribute), rva, rva + image_base),
+      None => format!("{}{}\n", indent, write_attribute(attribute))
+    };
 
-    let fmt = format!("// Image {}: {}\n", i, name);
     output.push_str(fmt.as_str());
   }
 
-  Ok(output)
+  output
 }
 
-fn write_fields(class: *const Il2CppClass, is_valuetype: bool) -> Result<String, Box<dyn Error>> {
-  let il2cpp = api::get_il2cpp_api()?;
+pub fn write_field_modifiers(flags: i32) -> String {
+  let access = match flags & FIELD_ATTRIBUTE_FIELD_ACCESS_MASK {
+    FIELD_ATTRIBUTE_PRIVATE => "private ",
+    FIELD_ATTRIBUTE_PUBLIC => "public ",
+    FIELD_ATTRIBUTE_FAMILY => "protected ",
+    FIELD_ATTRIBUTE_ASSEMBLY | FIELD_ATTRIBUTE_FAM_AND_ASSEM => "internal ",
+    FIELD_ATTRIBUTE_FAM_OR_ASSEM => "protected internal ",
+    _ => ""
+  };
+
+  let mut output = access.to_string();
+
+  if flags & FIELD_ATTRIBUTE_LITERAL != 0 {
+    output.push_str("const ");
+  }
+  else {
+    if flags & FIELD_ATTRIBUTE_STATIC != 0 {
+      output.push_str("static ");
+    }
+
+    if flags & FIELD_ATTRIBUTE_INIT_ONLY != 0 {
+      output.push_str("readonly ");
+    }
+  }
+
+  output
+}
+
+fn write_fields(class: &Class, image_base: usize) -> Result<String, Box<dyn Error>> {
   let mut output = String::new();
 
   output.push_str("\n\t// Fields\n");
 
-  let field_iter: *const c_void = null();
+  for field in &class.fields {
+    output.push_str(write_attributes(&field.attributes, "\t", image_base).as_str());
 
-  while let Some(field) = il2cpp.class_get_fields(class, &field_iter)? {
-    output.push_str("\t");
+    if class.kind == ClassKind::Enum {
+      // The instance field only holds the underlying type, which is part of the declaration
+      if field.flags & FIELD_ATTRIBUTE_STATIC == 0 {
+        continue;
+      }
 
-    let flags = il2cpp.field_get_flags(field)?;
-    let access = flags & FIELD_ATTRIBUTE_FIELD_ACCESS_MASK;
+      let fmt = match &field.value {
+        Some(value) => format!("\t{} = {},\n", field.name, value),
+        None => format!("\t{}, // value could not be read\n", field.name)
+      };
 
-    let access_str = match access {
-      FIELD_ATTRIBUTE_PRIVATE => "private ",
-      FIELD_ATTRIBUTE_PUBLIC => "public ",
-      FIELD_ATTRIBUTE_FAMILY => "protected ",
-      FIELD_ATTRIBUTE_ASSEMBLY | FIELD_ATTRIBUTE_FAM_AND_ASSEM => "internal ",
-      FIELD_ATTRIBUTE_FAM_OR_ASSEM => "protected internal ",
-      _ => ""
-    };
+      output.push_str(fmt.as_str());
+      continue;
+    }
 
-    output.push_str(access_str);
+    output.push('\t');
+    output.push_str(write_field_modifiers(field.flags).as_str());
 
-    let mut is_static = false;
+    let flags = field.flags;
 
-    if flags & FIELD_ATTRIBUTE_LITERAL != 0 {
-      output.push_str("const ");
+    let fmt = if flags & FIELD_ATTRIBUTE_LITERAL != 0 {
+      match &field.value {
+        Some(value) => format!("{} {} = {};\n", field.type_name, field.name, value),
+        None => format!("{} {}; // value could not be read\n", field.type_name, field.name)
+      }
+    }
+    else if flags & FIELD_ATTRIBUTE_HAS_DEFAULT != 0 {
+      match &field.value {
+        Some(value) => format!("{} {} = {}; // 0x{:x}\n", field.type_name, field.name, value, field.offset),
+        None => format!("{} {}; // 0x{:x}, default value could not be read\n", field.type_name, field.name, field.offset)
+      }
+    }
+    else if field.thread_static {
+      format!("{} {}; // thread static\n", field.type_name, field.name)
     }
     else {
-      if flags & FIELD_ATTRIBUTE_STATIC != 0 {
-        is_static = true;
-        output.push_str("static ");
+      let mut comment = format!("0x{:x}", field.offset);
+
+      if let Some(address) = field.address {
+        comment.push_str(format!(", address: 0x{:x}", address).as_str());
       }
 
-      if flags & FIELD_ATTRIBUTE_INIT_ONLY != 0 {
-        output.push_str("readonly ");
+      if let Some(snapshot) = &field.snapshot {
+        comment.push_str(format!(", value: {}", snapshot).as_str());
       }
-    }
 
-    let field_type = il2cpp.field_get_type(field)?;
-    let mut field_offset = il2cpp.field_get_offset(field)?;
+      format!("{} {}; // {}\n", field.type_name, field.name, comment)
+    };
+
+    output.push_str(fmt.as_str());
+  }
+
+  Ok(output)
+}
+
+fn write_method_access(flags: i32) -> &'static str {
+  match flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK {
+    METHOD_ATTRIBUTE_PRIVATE => "private ",
+    METHOD_ATTRIBUTE_PUBLIC => "public ",
+    METHOD_ATTRIBUTE_FAMILY => "protected ",
+    METHOD_ATTRIBUTE_ASSEM | METHOD_ATTRIBUTE_FAM_AND_ASSEM => "internal ",
+    METHOD_ATTRIBUTE_FAM_OR_ASSEM => "protected internal ",
+    _ => ""
+  }
+}
 
-    if is_valuetype && !is_static && field_offset > 0 {
-      field_offset -= 0x10;
+pub fn write_method_modifiers(flags: i32) -> String {
+  let mut output = String::new();
+
+  output.push_str(write_method_access(flags));
+
+  if flags & METHOD_ATTRIBUTE_STATIC != 0 {
+    output.push_str("static ");
+  }
+
+  if flags & METHOD_ATTRIBUTE_ABSTRACT != 0 {
+    output.push_str("abstract ");
+
+    if flags & METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK == METHOD_ATTRIBUTE_REUSE_SLOT {
+      output.push_str("override ");
     }
+  }
+  else if flags & METHOD_ATTRIBUTE_FINAL != 0 && flags & METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK == METHOD_ATTRIBUTE_REUSE_SLOT {
+    output.push_str("sealed override ");
+  }
+  else if flags & METHOD_ATTRIBUTE_VIRTUAL != 0 {
+    if flags & METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK == METHOD_ATTRIBUTE_NEW_SLOT {
+      output.push_str("virtual ");
+    }
+    else {
+      output.push_str("override ");
+    }
+  }
+
+  if flags & METHOD_ATTRIBUTE_PINVOKE_IMPL != 0 {
+    output.push_str("extern ");
+  }
+
+  output
+}
+
+fn write_accessor_rvas(accessors: &[(&str, &Option<Accessor>)], image_base: usize) -> String {
+  let rvas: Vec<String> = accessors.iter()
+    .filter_map(|(name, accessor)| accessor.as_ref().map(|accessor| format!("{} {}", name, write_code_location(&accessor.code, image_base))))
+    .collect();
+
+  format!("// {}", rvas.join(", "))
+}
+
+fn write_properties(class: &Class, image_base: usize) -> Result<String, Box<dyn Error>> {
+  let mut output = String::new();
+
+  if class.properties.is_empty() {
+    return Ok(output);
+  }
+
+  output.push_str("\n\t// Properties\n");
+
+  for property in &class.properties {
+    // The property itself is as visible as its most visible accessor
+    let flags = [&property.getter, &property.setter].iter()
+      .filter_map(|accessor| accessor.as_ref())
+      .map(|accessor| accessor.flags)
+      .max_by_key(|flags| flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK)
+      .unwrap_or(0);
 
-    let type_name = il2cpp.type_get_name(field_type)?;
-    let field_name = il2cpp.field_get_name(field)?;
+    let mut accessors = String::new();
 
-    let fmt = format!("{} {}; // 0x{:x}\n", type_name, field_name, field_offset);
+    for (name, accessor) in [("get", &property.getter), ("set", &property.setter)] {
+      if let Some(accessor) = accessor {
+        if accessor.flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK != flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK {
+          accessors.push_str(write_method_access(accessor.flags));
+        }
+
+        let fmt = format!("{}; ", name);
+        accessors.push_str(fmt.as_str());
+      }
+    }
+
+    let rvas = write_accessor_rvas(&[("get", &property.getter), ("set", &property.setter)], image_base);
+    let fmt = format!("\t{}{} {} {{ {}}} {}\n", write_method_modifiers(flags), property.type_name, property.name, accessors, rvas);
     output.push_str(fmt.as_str());
   }
 
   Ok(output)
 }
 
-fn write_methods(class: *const Il2CppClass) -> Result<String, Box<dyn Error>> {
-  let il2cpp = api::get_il2cpp_api()?;
+fn write_events(class: &Class, image_base: usize) -> Result<String, Box<dyn Error>> {
   let mut output = String::new();
 
-  output.push_str("\n\t// Methods\n");
+  if class.events.is_empty() {
+    return Ok(output);
+  }
 
-  let method_iter: *const c_void = null();
+  output.push_str("\n\t// Events\n");
 
-  while let Some(method) = il2cpp.class_get_methods(class, &method_iter)? {
-    output.push_str("\n");
+  for event in &class.events {
+    let flags = event.add.as_ref().or(event.remove.as_ref()).map(|accessor| accessor.flags).unwrap_or(0);
+    let rvas = write_accessor_rvas(&[("add", &event.add), ("remove", &event.remove), ("raise", &event.raise)], image_base);
 
-    let pointer = unsafe { (*method).method_pointer as usize };
+    let fmt = format!("\t{}event {} {}; {}\n", write_method_modifiers(flags), event.type_name, event.name, rvas);
+    output.push_str(fmt.as_str());
+  }
 
-    if pointer != 0 {
-      let offset = pointer - il2cpp.game_assembly.handle as usize;
-      let fmt = format!("\t// RVA: 0x{:x} VA: 0x{:x}\n\t", offset, offset + 0x180000000);
-      output.push_str(fmt.as_str());
-    }
-    else {
-      output.push_str("\t// RVA: 0x0 VA: 0x0\n\t");
-    }
+  Ok(output)
+}
 
-    let flags = unsafe { (*method).flags } as i32;
-    let access = flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK;
+fn write_internal_call(call: &InternalCall) -> String {
+  match (&call.module, call.rva) {
+    (Some(module), Some(rva)) => format!("\t// Internal call: {} -> {}+0x{:x}\n", call.name, module, rva),
+    _ => format!("\t// Internal call: {} (not registered)\n", call.name)
+  }
+}
 
-    let access_str = match access {
-      METHOD_ATTRIBUTE_PRIVATE => "private ",
-      METHOD_ATTRIBUTE_PUBLIC => "public ",
-      METHOD_ATTRIBUTE_FAMILY => "protected ",
-      METHOD_ATTRIBUTE_ASSEM | METHOD_ATTRIBUTE_FAM_AND_ASSEM => "internal ",
-      METHOD_ATTRIBUTE_FAM_OR_ASSEM => "protected internal ",
-      _ => ""
-    };
+// Only code in GameAssembly has a VA, code elsewhere names its module and methods without code say why
+fn write_code_location(code: &MethodCode, image_base: usize) -> String {
+  match (code.kind, &code.module, code.rva) {
+    (CodeKind::Compiled, _, Some(rva)) => format!("RVA: 0x{:x} VA: 0x{:x}", rva, rva + image_base),
+    (CodeKind::External, Some(module), Some(rva)) => format!("RVA: 0x{:x} Module: {}", rva, module),
+    (kind, ..) => format!("RVA: -1 ({})", kind.as_str())
+  }
+}
 
-    output.push_str(access_str);
+fn write_method_code(code: &MethodCode, image_base: usize) -> String {
+  format!("\t// {}\n\t", write_code_location(code, image_base))
+}
 
-    if flags & METHOD_ATTRIBUTE_STATIC != 0 {
-      output.push_str("static ");
+// Instances sharing the same code are grouped under one RVA, the way Il2CppDumper lists them
+fn write_generic_instances(instances: &[GenericInstance], image_base: usize) -> String {
+  let mut groups: Vec<(&MethodCode, Vec<&str>)> = vec![];
+
+  for instance in instances {
+    match groups.iter_mut().find(|(code, _)| **code == instance.code) {
+      Some((_, names)) => names.push(&instance.name),
+      None => groups.push((&instance.code, vec![&instance.name]))
     }
+  }
 
-    if flags & METHOD_ATTRIBUTE_ABSTRACT != 0 {
-      output.push_str("abstract ");
+  let mut output = String::from("\t/* GenericInstMethod :\n");
 
-      if flags & METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK == METHOD_ATTRIBUTE_REUSE_SLOT {
-        output.push_str("override ");
-      }
+  for (code, names) in groups {
+    let fmt = format!("\t|\n\t|-{}\n", write_code_location(code, image_base));
+    output.push_str(fmt.as_str());
+
+    for name in names {
+      let fmt = format!("\t|-{}\n", name);
+      output.push_str(fmt.as_str());
     }
-    else if flags & METHOD_ATTRIBUTE_FINAL != 0 && flags & METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK == METHOD_ATTRIBUTE_REUSE_SLOT {
-      output.push_str("sealed override ");
+  }
+
+  output.push_str("\t*/\n");
+  output
+}
+
+pub fn write_param_modifiers(param: &Param) -> String {
+  let attrs = param.attrs;
+  let mut output = String::new();
+
+  if param.byref {
+    if attrs & PARAM_ATTRIBUTE_OUT != 0 && attrs & PARAM_ATTRIBUTE_IN == 0 {
+      output.push_str("out ");
     }
-    else if flags & METHOD_ATTRIBUTE_VIRTUAL != 0 {
-      if flags & METHOD_ATTRIBUTE_VTABLE_LAYOUT_MASK == METHOD_ATTRIBUTE_NEW_SLOT {
-        output.push_str("virtual ");
-      }
-      else {
-        output.push_str("override ");
-      }
+    else if attrs & PARAM_ATTRIBUTE_IN != 0 && attrs & PARAM_ATTRIBUTE_OUT == 0 {
+      output.push_str("in ");
+    }
+    else {
+      output.push_str("ref ");
+    }
+  }
+  else {
+    if attrs & PARAM_ATTRIBUTE_OPTIONAL != 0 {
+      output.push_str("[Optional] ");
+    }
+
+    if attrs & PARAM_ATTRIBUTE_IN != 0 {
+      output.push_str("[In] ");
     }
 
-    if flags & METHOD_ATTRIBUTE_PINVOKE_IMPL != 0 {
-      output.push_str("extern ");
+    if attrs & PARAM_ATTRIBUTE_OUT != 0 {
+      output.push_str("[Out] ");
     }
+  }
 
-    let return_type = il2cpp.method_get_return_type(method)?;
+  output
+}
 
-    if il2cpp.type_is_byref(return_type)? {
-      output.push_str("ref ");
+fn write_methods(class: &Class, game: &Game) -> Result<String, Box<dyn Error>> {
+  let mut output = String::new();
+
+  output.push_str("\n\t// Methods\n");
+
+  for method in &class.methods {
+    output.push('\n');
+    output.push_str(write_attributes(&method.attributes, "\t", game.image_base).as_str());
+
+    if let Some(call) = &method.internal_call {
+      output.push_str(write_internal_call(call).as_str());
     }
 
-    let return_name = il2cpp.type_get_name(return_type)?;
-    let method_name = il2cpp.method_get_name(method)?;
+    output.push_str(write_method_code(&method.code, game.image_base).as_str());
 
-    let fmt = format!("{} {}(", return_name, method_name);
-    output.push_str(fmt.as_str());
+    output.push_str(write_method_modifiers(method.flags).as_str());
 
-    let param_count = il2cpp.method_get_param_count(method)?;
+    if method.return_byref {
+      output.push_str("ref ");
+    }
 
-    for i in 0..param_count {
-      let param = il2cpp.method_get_param(method, i)?;
-      let attrs = il2cpp.type_get_attrs(param)? as i32;
+    let fmt = format!("{} {}{}(", method.return_type, method.name, write_generic_params(&method.generic_params));
+    output.push_str(fmt.as_str());
 
-      if il2cpp.type_is_byref(param)? {
-        if attrs & PARAM_ATTRIBUTE_OUT != 0 && attrs & PARAM_ATTRIBUTE_IN == 0 {
-          output.push_str("out ");
-        }
-        else if attrs & PARAM_ATTRIBUTE_IN != 0 && attrs & PARAM_ATTRIBUTE_OUT == 0 {
-          output.push_str("in ");
-        }
-        else {
-          output.push_str("ref ");
-        }
+    for (i, param) in method.params.iter().enumerate() {
+      for attribute in &param.attributes {
+        output.push_str(format!("{} ", write_attribute(attribute)).as_str());
       }
-      else {
-        if attrs & PARAM_ATTRIBUTE_IN != 0 {
-          output.push_str("[In] ");
-        }
 
-        if attrs & PARAM_ATTRIBUTE_OUT != 0 {
-          output.push_str("[Out] ");
-        }
-      }
+      output.push_str(write_param_modifiers(param).as_str());
 
-      let type_name = il2cpp.type_get_name(param)?;
-      let fmt = format!("{}{}", type_name, if i != param_count - 1 { ", " } else { "" });
+      let fmt = format!("{} {}", param.type_name, write_param_name(&param.name, i));
       output.push_str(fmt.as_str());
+
+      if param.attrs & PARAM_ATTRIBUTE_HAS_DEFAULT != 0 {
+        let fmt = match &param.default {
+          Some(value) => format!(" = {}", value),
+          None => " /* default value could not be read */".to_string()
+        };
+
+        output.push_str(fmt.as_str());
+      }
+
+      if i != method.params.len() - 1 {
+        output.push_str(", ");
+      }
     }
 
-    output.push_str(") { }\n");
+    output.push(')');
+    output.push_str(write_generic_constraints(&method.generic_params).as_str());
+    output.push_str(" { }\n");
+
+    if let Some(instances) = game.instances.get(&method.handle) {
+      output.push_str(write_generic_instances(instances, game.image_base).as_str());
+    }
   }
 
   Ok(output)
 }
 
-fn write_class(class: *const Il2CppClass) -> Result<String, Box<dyn Error>> {
-  let il2cpp = api::get_il2cpp_api()?;
+fn write_layout(class: &Class) -> String {
+  let layout = &class.layout;
+
+  let mut output = format!("// Size: 0x{:x}, Static size: 0x{:x}, Element size: 0x{:x}, Alignment: {}\n",
+    layout.instance_size, layout.static_size, layout.element_size, layout.alignment);
+
+  if let Some(static_fields) = class.static_fields {
+    output.push_str(format!("// Static fields: 0x{:x}\n", static_fields).as_str());
+  }
+
+  output
+}
+
+fn write_vtable(class: &Class, image_base: usize) -> String {
+  let mut output = String::new();
+
+  if class.vtable.is_empty() {
+    return output;
+  }
+
+  output.push_str("\n\t// VTable\n");
+
+  for slot in &class.vtable {
+    let method = slot.method.as_deref().unwrap_or("<empty>");
+
+    let fmt = match slot.rva {
+      Some(rva) => format!("\t// [{}] {} // RVA: 0x{:x} VA: 0x{:x}\n", slot.slot, method, rva, rva + image_base),
+      None => format!("\t// [{}] {}\n", slot.slot, method)
+    };
+
+    output.push_str(fmt.as_str());
+  }
+
+  output
+}
+
+fn write_class(class: &Class, game: &Game) -> Result<String, Box<dyn Error>> {
   let mut output = String::new();
 
-  let namespace = il2cpp.class_get_namespace(class)?;
-  let fmt = format!("\n// Namespace: {}\n", namespace);
-  output.push_str(fmt.as_str());
+  output.push_str(write_layout(class).as_str());
 
-  let flags = il2cpp.class_get_flags(class)?;
+  let flags = class.flags;
 
   if flags & TYPE_ATTRIBUTE_SERIALIZABLE != 0 {
     output.push_str("[Serializable]\n");
   }
 
+  output.push_str(write_attributes(&class.attributes, "", game.image_base).as_str());
+
   let visibility = flags & TYPE_ATTRIBUTE_VISIBILITY_MASK;
 
   let visibility_str = match visibility {
@@ -226,106 +495,304 @@ fn write_class(class: *const Il2CppClass) -> Result<String, Box<dyn Error>> {
 
   output.push_str(visibility_str);
 
-  let is_valuetype = il2cpp.class_is_valuetype(class)?;
-  let is_enum = il2cpp.class_is_enum(class)?;
+  let is_valuetype = class.kind == ClassKind::Struct || class.kind == ClassKind::Enum;
 
   if flags & TYPE_ATTRIBUTE_ABSTRACT != 0 && flags & TYPE_ATTRIBUTE_SEALED != 0 {
     output.push_str("static ");
   }
-  else if !(flags & TYPE_ATTRIBUTE_INTERFACE != 0) && flags & TYPE_ATTRIBUTE_ABSTRACT != 0 {
+  else if class.kind != ClassKind::Interface && flags & TYPE_ATTRIBUTE_ABSTRACT != 0 {
     output.push_str("abstract ");
   }
-  else if !is_valuetype && !is_enum && flags & TYPE_ATTRIBUTE_SEALED != 0 {
+  else if !is_valuetype && flags & TYPE_ATTRIBUTE_SEALED != 0 {
     output.push_str("sealed ");
   }
 
-  if flags & TYPE_ATTRIBUTE_INTERFACE != 0 {
-    output.push_str("interface ");
-  }
-  else if is_enum {
-    output.push_str("enum ");
-  }
-  else if is_valuetype {
-    output.push_str("struct ");
-  }
-  else {
-    output.push_str("class ");
-  }
+  output.push_str(class.kind.as_str());
+  output.push(' ');
+  output.push_str(class.display_name().as_str());
 
-  let name = il2cpp.class_get_name(class)?;
-  output.push_str(name.as_str());
+  let mut extends = vec![];
 
-  let mut extends = vec![]; 
-
-  if let Some(parent) = il2cpp.class_get_parent(class).ok() {
-    let name = il2cpp.class_get_name(parent)?;
-
-    if !is_valuetype && !is_enum && name != "Object" {
-      extends.push(name);
+  if let Some(parent) = &class.parent {
+    if !is_valuetype && !parent.is_primitive(IL2CPP_TYPE_OBJECT) {
+      extends.push(parent.to_string());
     }
   }
 
-  let interface_ier: *const c_void = null(); 
-
-  while let Some(interface) = il2cpp.class_get_interfaces(class, &interface_ier)? {
-    let name = il2cpp.class_get_name(interface)?;
-    extends.push(name);
+  // Enums can only "inherit" their underlying type, and only a non-default one is worth spelling out
+  if let Some(underlying_type) = class.enum_underlying_type() {
+    if !underlying_type.is_primitive(IL2CPP_TYPE_I4) {
+      extends.push(underlying_type.to_string());
+    }
   }
 
+  extends.extend(class.interfaces.iter().map(TypeName::to_string));
+
   if !extends.is_empty() {
     let fmt = format!(" : {}", extends.join(", "));
     output.push_str(fmt.as_str());
   }
 
+  output.push_str(write_generic_constraints(class.declared_generic_params()).as_str());
+
   output.push_str("\n{");
-  output.push_str(write_fields(class, is_valuetype)?.as_str());
-  output.push_str(write_methods(class)?.as_str());
+  output.push_str(write_fields(class, game.image_base)?.as_str());
+  output.push_str(write_properties(class, game.image_base)?.as_str());
+  output.push_str(write_events(class, game.image_base)?.as_str());
+  output.push_str(write_methods(class, game)?.as_str());
+  output.push_str(write_vtable(class, game.image_base).as_str());
+
+  for nested_type in &class.nested_types {
+    output.push('\n');
+    output.push_str(indent(&write_class(nested_type, game)?).as_str());
+  }
+
   output.push_str("}\n");
 
   Ok(output)
 }
 
-fn write_classes() -> Result<String, Box<dyn Error>> {
-  let il2cpp = api::get_il2cpp_api()?;
+// Classes are written one at a time and flushed after every image, so a dump that fails halfway still has everything before it
+fn write_classes(game: &Game, output: &mut impl Write) -> Result<usize, Box<dyn Error>> {
+  let mut class_count = 0;
+
+  for entry in &game.images {
+    for class in &entry.classes {
+      write!(output, "\n// Namespace: {}\n{}", class.namespace, write_class(class, game)?)?;
+      class_count += 1;
+    }
+
+    output.flush()?;
+  }
+
+  Ok(class_count)
+}
+
+fn indent(text: &str) -> String {
   let mut output = String::new();
 
-  let domain = il2cpp.domain_get()?;
+  for line in text.lines() {
+    if !line.is_empty() {
+      output.push('\t');
+    }
+
+    output.push_str(line);
+    output.push('\n');
+  }
+
+  output
+}
+
+// Full name to namespace of every class, nested classes can't be told apart from namespaces by their name alone
+fn collect_namespaces(game: &Game) -> HashMap<String, String> {
+  game.all_classes().into_iter()
+    .map(|(_, class)| (class.full_name(), class.namespace.clone()))
+    .collect()
+}
+
+// Namespaces of every type the class and its nested types refer to
+fn collect_usings(class: &Class, namespaces: &HashMap<String, String>, usings: &mut BTreeSet<String>) {
+  let mut types: Vec<&TypeName> = class.parent.iter().chain(&class.interfaces).collect();
 
-  let assembly_size: usize = 0;
-  let assemblies = il2cpp.domain_get_assemblies(domain, &assembly_size)?;
+  types.extend(class.fields.iter().map(|field| &field.type_name));
+  types.extend(class.properties.iter().map(|property| &property.type_name));
+  types.extend(class.events.iter().map(|event| &event.type_name));
 
-  for i in 0..assembly_size {
-    let assembly = unsafe { *assemblies.offset(i as isize) };
+  for method in &class.methods {
+    types.push(&method.return_type);
+    types.extend(method.params.iter().map(|param| &param.type_name));
+  }
 
-    if assembly.is_null() {
+  for type_name in types {
+    for name in type_name.class_names() {
+      if let Some(namespace) = namespaces.get(name) {
+        usings.insert(namespace.clone());
+      }
+    }
+  }
+
+  if class.flags & TYPE_ATTRIBUTE_SERIALIZABLE != 0 {
+    usings.insert("System".to_string());
+  }
+
+  for nested_type in &class.nested_types {
+    collect_usings(nested_type, namespaces, usings);
+  }
+}
+
+// A source file with the using directives and the classes wrapped in their namespace, each class is written
+// to the file as soon as it's formatted so a namespace never has to be held in memory as a whole
+fn write_source_file(path: &Path, image: &Image, namespace: &str, classes: &[&Class], namespaces: &HashMap<String, String>, game: &Game, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let mut usings = BTreeSet::new();
+
+  for class in classes {
+    collect_usings(class, namespaces, &mut usings);
+  }
+
+  if let Some(directory) = path.parent() {
+    fs::create_dir_all(directory)?;
+  }
+
+  let file_name = compression.file_name(&path.to_string_lossy());
+  let mut sink = Sink::create(file_name, compression)?;
+
+  writeln!(sink, "// Image {}: {}", image.index, image.name)?;
+
+  let usings: Vec<&String> = usings.iter().filter(|using| !using.is_empty() && *using != namespace).collect();
+
+  if !usings.is_empty() {
+    writeln!(sink)?;
+  }
+
+  for using in usings {
+    writeln!(sink, "using {};", using)?;
+  }
+
+  if namespace.is_empty() {
+    writeln!(sink)?;
+  }
+  else {
+    write!(sink, "\nnamespace {}\n{{\n", namespace)?;
+  }
+
+  for (i, class) in classes.iter().enumerate() {
+    if i > 0 {
+      writeln!(sink)?;
+    }
+
+    let output = write_class(class, game)?;
+    sink.write_all(if namespace.is_empty() { output } else { indent(&output) }.as_bytes())?;
+  }
+
+  if !namespace.is_empty() {
+    writeln!(sink, "}}")?;
+  }
+
+  sink.finish()?;
+
+  Ok(())
+}
+
+// Names that Windows doesn't allow in paths, e.g. the <> of compiler generated types
+fn path_segment(name: &str) -> String {
+  name.chars().map(|c| if "<>:\"/\\|?*".contains(c) { '_' } else { c }).collect()
+}
+
+fn image_directory(image: &Image) -> PathBuf {
+  let name = image.name.strip_suffix(".dll").unwrap_or(&image.name);
+  PathBuf::from("dump").join(path_segment(name))
+}
+
+// Windows paths are case-insensitive and path_segment can map different names to the same one,
+// so a path that's already taken gets a _1, _2 suffix in the order the classes are written in
+fn unique_path(path: PathBuf, taken: &mut HashSet<String>) -> PathBuf {
+  let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+  let mut candidate = path.clone();
+  let mut count = 0;
+
+  while !taken.insert(candidate.to_string_lossy().to_lowercase()) {
+    count += 1;
+    candidate = path.with_file_name(format!("{}_{}.cs", stem, count));
+  }
+
+  candidate
+}
+
+// Everything from earlier dumps is removed so types that no longer exist don't leave stale files behind,
+// hidden entries like .git are kept so the folder can still be a repository that's diffed between versions
+fn clear_dump_directory() -> Result<(), Box<dyn Error>> {
+  let directory = Path::new("dump");
+
+  if !directory.exists() {
+    return Ok(());
+  }
+
+  for entry in fs::read_dir(directory)? {
+    let entry = entry?;
+
+    if entry.file_name().to_string_lossy().starts_with('.') {
       continue;
     }
 
-    let image = il2cpp.assembly_get_image(assembly)?;
-    let class_count = il2cpp.image_get_class_count(image)?;
+    if entry.file_type()?.is_dir() {
+      fs::remove_dir_all(entry.path())?;
+    }
+    else {
+      fs::remove_file(entry.path())?;
+    }
+  }
+
+  Ok(())
+}
 
-    for j in 0..class_count {
-      let class = il2cpp.image_get_class(image, j)?;
+fn namespace_directory(image: &Image, namespace: &str) -> PathBuf {
+  let mut path = image_directory(image);
 
-      if class.is_null() {
-        continue;
+  for segment in namespace.split('.').filter(|segment| !segment.is_empty()) {
+    path.push(path_segment(segment));
+  }
+
+  path
+}
+
+fn write_split(game: &Game, layout: CsLayout, compression: Compression) -> Result<usize, Box<dyn Error>> {
+  clear_dump_directory()?;
+
+  let namespaces = collect_namespaces(game);
+
+  let mut taken = HashSet::new();
+  let mut file_count = 0;
+
+  for ImageClasses { image, classes } in &game.images {
+    if layout == CsLayout::PerClass {
+      for class in classes {
+        let path = unique_path(namespace_directory(image, &class.namespace).join(format!("{}.cs", path_segment(&class.name))), &mut taken);
+
+        write_source_file(&path, image, &class.namespace, &[class], &namespaces, game, compression)?;
+        file_count += 1;
+      }
+
+      continue;
+    }
+
+    let mut grouped: BTreeMap<&str, Vec<&Class>> = BTreeMap::new();
+
+    for class in classes {
+      grouped.entry(class.namespace.as_str()).or_default().push(class);
+    }
+
+    for (namespace, classes) in grouped {
+      let path = unique_path(if namespace.is_empty() {
+        image_directory(image).join("global.cs")
       }
+      else {
+        namespace_directory(image, namespace).with_extension("cs")
+      }, &mut taken);
 
-      output.push_str(write_class(class)?.as_str());
+      write_source_file(&path, image, namespace, &classes, &namespaces, game, compression)?;
+      file_count += 1;
     }
   }
 
-  Ok(output)
+  Ok(file_count)
 }
 
-pub fn dump() -> Result<(), Box<dyn Error>> {
-  let mut output = String::new();
+pub fn dump(game: &Game, layout: CsLayout, compression: Compression) -> Result<(), Box<dyn Error>> {
+  if layout != CsLayout::SingleFile {
+    let file_count = write_split(game, layout, compression)?;
+    println!("{} files saved to the dump folder", file_count);
 
-  output.push_str(write_images()?.as_str());
-  output.push_str(write_classes()?.as_str());
+    return Ok(());
+  }
+
+  let file_name = compression.file_name("dump.cs");
+  let mut sink = Sink::create(&file_name, compression)?;
 
-  let mut file = File::create("dump.cs")?;
-  file.write_all(output.as_bytes())?;
+  write_images(game, &mut sink)?;
+  let class_count = write_classes(game, &mut sink)?;
+  sink.finish()?;
+
+  println!("{} classes saved to {}", class_count, file_name);
 
   Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,314 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, error::Error, io::Write};
+
+use crate::{model::{class::{Class, ClassKind}, game::{Game, ImageClasses}, image::Image, type_name::{strip_arity, TypeName}}, outputs::sink::{Compression, Sink}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+  // hierarchy.dot, for Graphviz, e.g. dot -Tsvg hierarchy.dot -o hierarchy.svg
+  Dot,
+  // hierarchy.graphml, for yEd, Gephi and other graph editors
+  GraphMl
+}
+
+impl GraphFormat {
+  pub fn file_name(&self) -> &'static str {
+    match self {
+      GraphFormat::Dot => "hierarchy.dot",
+      GraphFormat::GraphMl => "hierarchy.graphml"
+    }
+  }
+}
+
+// Which part of the hierarchy is saved, everything when no filter is set
+pub struct GraphFilter {
+  // Only types in this namespace or the ones below it, e.g. RPG.Client
+  pub namespace: Option<&'static str>,
+  // Only this type and the ones deriving from or implementing it, by full name, e.g. RPG.Client.BaseDialog
+  pub root: Option<&'static str>,
+  // How many levels below the root are included, ignored without a root
+  pub depth: Option<usize>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EdgeKind {
+  Extends,
+  Implements
+}
+
+impl EdgeKind {
+  fn as_str(&self) -> &'static str {
+    match self {
+      EdgeKind::Extends => "extends",
+      EdgeKind::Implements => "implements"
+    }
+  }
+}
+
+struct Node {
+  // Image qualified, names like <Module> are defined in every image
+  id: String,
+  name: String,
+  label: String,
+  kind: ClassKind,
+  image: String
+}
+
+struct Edge {
+  // Id of the node, the target is a type name until resolve_edges turns it into the id of a node if there's one
+  from: String,
+  image: String,
+  to: String,
+  kind: EdgeKind,
+  // The instantiated type for generic parents and interfaces, e.g. List<int>
+  label: Option<String>
+}
+
+#[derive(Default)]
+struct Hierarchy {
+  nodes: Vec<Node>,
+  edges: Vec<Edge>
+}
+
+fn node_id(image: &Image, name: &str) -> String {
+  format!("{}:{}", image.name, name)
+}
+
+fn collect_classes(class: &Class, image: &Image, hierarchy: &mut Hierarchy) {
+  let name = class.full_name();
+  let id = node_id(image, &name);
+  let prefix = &name[..name.len() - class.name.len()];
+
+  // Generic instances are drawn as an edge to their definition
+  let mut push_edge = |type_name: &TypeName, kind: EdgeKind| {
+    if let Some(to) = type_name.definition_name() {
+      let label = matches!(type_name, TypeName::GenericInstance { .. }).then(|| type_name.to_string());
+      hierarchy.edges.push(Edge { from: id.clone(), image: image.name.clone(), to, kind, label });
+    }
+  };
+
+  if let Some(parent) = &class.parent {
+    push_edge(parent, EdgeKind::Extends);
+  }
+
+  for interface in &class.interfaces {
+    push_edge(interface, EdgeKind::Implements);
+  }
+
+  hierarchy.nodes.push(Node {
+    label: format!("{}{}", prefix, class.display_name()),
+    id,
+    name,
+    kind: class.kind,
+    image: image.name.clone()
+  });
+
+  for nested_type in &class.nested_types {
+    collect_classes(nested_type, image, hierarchy);
+  }
+}
+
+fn in_namespace(name: &str, namespace: &str) -> bool {
+  name.strip_prefix(namespace).is_some_and(|rest| rest.starts_with('.'))
+}
+
+// Points edges at the node of their target, preferring the one in the same image when a name is defined in
+// several, targets without a node (types from images that failed to load) keep their name
+fn resolve_edges(hierarchy: &mut Hierarchy) {
+  let mut by_image: HashMap<(String, String), String> = HashMap::new();
+  let mut by_name: HashMap<String, String> = HashMap::new();
+
+  for node in &hierarchy.nodes {
+    by_image.entry((node.image.clone(), node.name.clone())).or_insert_with(|| node.id.clone());
+    by_name.entry(node.name.clone()).or_insert_with(|| node.id.clone());
+  }
+
+  for edge in &mut hierarchy.edges {
+    let resolved = by_image.get(&(edge.image.clone(), edge.to.clone())).or_else(|| by_name.get(&edge.to));
+
+    if let Some(id) = resolved {
+      edge.to = id.clone();
+    }
+  }
+}
+
+// Ids of the types that pass the filter, parents and interfaces outside of it are added as external nodes later
+fn select(hierarchy: &Hierarchy, filter: &GraphFilter) -> HashSet<String> {
+  let mut selected: HashSet<String> = hierarchy.nodes.iter()
+    .filter(|node| filter.namespace.is_none_or(|namespace| in_namespace(&node.name, namespace)))
+    .map(|node| node.id.clone())
+    .collect();
+
+  if let Some(root) = filter.root {
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for edge in &hierarchy.edges {
+      children.entry(edge.to.as_str()).or_default().push(edge.from.as_str());
+    }
+
+    let mut descendants = HashSet::new();
+
+    let mut queue: VecDeque<(&str, usize)> = hierarchy.nodes.iter()
+      .filter(|node| node.name == root)
+      .map(|node| (node.id.as_str(), 0))
+      .collect();
+
+    while let Some((id, depth)) = queue.pop_front() {
+      if !descendants.insert(id.to_string()) || filter.depth.is_some_and(|max_depth| depth >= max_depth) {
+        continue;
+      }
+
+      for &child in children.get(id).into_iter().flatten() {
+        queue.push_back((child, depth + 1));
+      }
+    }
+
+    selected.retain(|name| descendants.contains(name));
+  }
+
+  selected
+}
+
+fn dot_escape(text: &str) -> String {
+  text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn write_dot(output: &mut impl Write, nodes: &[&Node], external: &[(String, String)], edges: &[&Edge]) -> Result<(), Box<dyn Error>> {
+  writeln!(output, "digraph hierarchy {{")?;
+  writeln!(output, "  rankdir=BT;")?;
+  writeln!(output, "  node [shape=box, fontname=\"Helvetica\"];")?;
+  writeln!(output, "  edge [arrowhead=empty];")?;
+  writeln!(output)?;
+
+  for node in nodes {
+    let style = match node.kind {
+      ClassKind::Class => "",
+      ClassKind::Struct => ", style=rounded",
+      ClassKind::Enum => ", style=\"rounded,filled\", fillcolor=lightyellow",
+      ClassKind::Interface => ", style=filled, fillcolor=lightblue"
+    };
+
+    writeln!(output, "  \"{}\" [label=\"{}\", tooltip=\"{} {}\"{}];", dot_escape(&node.id), dot_escape(&node.label), node.kind.as_str(), dot_escape(&node.image), style)?;
+  }
+
+  for (id, label) in external {
+    writeln!(output, "  \"{}\" [label=\"{}\", style=dashed, color=gray, fontcolor=gray];", dot_escape(id), dot_escape(label))?;
+  }
+
+  writeln!(output)?;
+
+  for edge in edges {
+    let mut attributes = vec![];
+
+    if edge.kind == EdgeKind::Implements {
+      attributes.push("style=dashed".to_string());
+    }
+
+    if let Some(label) = &edge.label {
+      attributes.push(format!("label=\"{}\"", dot_escape(label)));
+    }
+
+    writeln!(output, "  \"{}\" -> \"{}\" [{}];", dot_escape(&edge.from), dot_escape(&edge.to), attributes.join(", "))?;
+  }
+
+  writeln!(output, "}}")?;
+
+  Ok(())
+}
+
+fn write_graphml(output: &mut impl Write, nodes: &[&Node], external: &[(String, String)], edges: &[&Edge]) -> Result<(), Box<dyn Error>> {
+  writeln!(output, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+  writeln!(output, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+  writeln!(output, "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>")?;
+  writeln!(output, "  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>")?;
+  writeln!(output, "  <key id=\"image\" for=\"node\" attr.name=\"image\" attr.type=\"string\"/>")?;
+  writeln!(output, "  <key id=\"external\" for=\"node\" attr.name=\"external\" attr.type=\"boolean\"><default>false</default></key>")?;
+  writeln!(output, "  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>")?;
+  writeln!(output, "  <key id=\"type\" for=\"edge\" attr.name=\"type\" attr.type=\"string\"/>")?;
+  writeln!(output, "  <graph id=\"hierarchy\" edgedefault=\"directed\">")?;
+
+  for node in nodes {
+    writeln!(output, "    <node id=\"{}\">", xml_escape(&node.id))?;
+    writeln!(output, "      <data key=\"label\">{}</data>", xml_escape(&node.label))?;
+    writeln!(output, "      <data key=\"kind\">{}</data>", node.kind.as_str())?;
+    writeln!(output, "      <data key=\"image\">{}</data>", xml_escape(&node.image))?;
+    writeln!(output, "    </node>")?;
+  }
+
+  for (id, label) in external {
+    writeln!(output, "    <node id=\"{}\">", xml_escape(id))?;
+    writeln!(output, "      <data key=\"label\">{}</data>", xml_escape(label))?;
+    writeln!(output, "      <data key=\"external\">true</data>")?;
+    writeln!(output, "    </node>")?;
+  }
+
+  for edge in edges {
+    writeln!(output, "    <edge source=\"{}\" target=\"{}\">", xml_escape(&edge.from), xml_escape(&edge.to))?;
+    writeln!(output, "      <data key=\"relation\">{}</data>", edge.kind.as_str())?;
+
+    if let Some(label) = &edge.label {
+      writeln!(output, "      <data key=\"type\">{}</data>", xml_escape(label))?;
+    }
+
+    writeln!(output, "    </edge>")?;
+  }
+
+  writeln!(output, "  </graph>")?;
+  writeln!(output, "</graphml>")?;
+
+  Ok(())
+}
+
+// Saves the inheritance and interface graph, edges point from a type to its parent and interfaces
+pub fn dump(game: &Game, format: GraphFormat, filter: &GraphFilter, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let mut hierarchy = Hierarchy::default();
+
+  for ImageClasses { image, classes } in &game.images {
+    for class in classes {
+      collect_classes(class, image, &mut hierarchy);
+    }
+  }
+
+  resolve_edges(&mut hierarchy);
+
+  if let Some(root) = filter.root {
+    if !hierarchy.nodes.iter().any(|node| node.name == root) {
+      println!("graph root {} not found, the graph will be empty", root);
+    }
+  }
+
+  let selected = select(&hierarchy, filter);
+
+  let nodes: Vec<&Node> = hierarchy.nodes.iter().filter(|node| selected.contains(&node.id)).collect();
+  let edges: Vec<&Edge> = hierarchy.edges.iter().filter(|edge| selected.contains(&edge.from)).collect();
+
+  // Targets outside the selection, labelled like their node or by name when they have none
+  let labels: HashMap<&str, &str> = hierarchy.nodes.iter().map(|node| (node.id.as_str(), node.label.as_str())).collect();
+
+  let mut external = vec![];
+  let mut seen = HashSet::new();
+
+  for edge in &edges {
+    if !selected.contains(&edge.to) && seen.insert(edge.to.as_str()) {
+      let label = labels.get(edge.to.as_str()).map(|label| label.to_string()).unwrap_or_else(|| strip_arity(&edge.to));
+      external.push((edge.to.clone(), label));
+    }
+  }
+
+  let file_name = compression.file_name(format.file_name());
+  let mut sink = Sink::create(&file_name, compression)?;
+
+  match format {
+    GraphFormat::Dot => write_dot(&mut sink, &nodes, &external, &edges)?,
+    GraphFormat::GraphMl => write_graphml(&mut sink, &nodes, &external, &edges)?
+  }
+
+  sink.finish()?;
+
+  println!("{} types and {} relations saved to {}", nodes.len(), edges.len(), file_name);
+
+  Ok(())
+}
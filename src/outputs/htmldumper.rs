@@ -0,0 +1,452 @@
+use std::{collections::{BTreeMap, HashMap}, error::Error, fmt::Write as _, fs, io::Write, path::Path};
+use serde_json::json;
+
+use crate::{il2cpp::constants::*, model::{class::{Class, ClassKind}, game::{Game, ImageClasses}, image::Image, type_name::{nested_arguments, strip_arity, TypeName}}, outputs::{csdumper::{write_field_modifiers, write_generic_params, write_method_modifiers, write_param_modifiers, write_param_name}, sink::{Compression, Sink}}};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em auto; max-width: 80em; padding: 0 1em; color: #222; }
+a { color: #0650a0; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav, .inheritance { color: #666; }
+.kind { color: #888; font-weight: normal; }
+code, td { font-family: monospace; }
+table { border-collapse: collapse; width: 100%; }
+td { padding: 0.2em 0.6em; border-bottom: 1px solid #eee; vertical-align: top; }
+td.modifiers, td.address { color: #888; white-space: nowrap; }
+details { margin-left: 1em; }
+summary { cursor: pointer; }
+#search { font-size: 1.1em; padding: 0.3em; width: 30em; max-width: 100%; }
+";
+
+const SEARCH_SCRIPT: &str = "const input = document.getElementById('search');
+const results = document.getElementById('results');
+
+input.addEventListener('input', () => {
+  const query = input.value.toLowerCase();
+  results.replaceChildren();
+
+  if (query.length == 0) {
+    return;
+  }
+
+  let count = 0;
+
+  for (const [name, id, kind] of TYPES) {
+    if (!name.toLowerCase().includes(query)) {
+      continue;
+    }
+
+    const link = document.createElement('a');
+    link.href = 'types/' + id + '.html';
+    link.textContent = name;
+
+    const item = document.createElement('li');
+    item.append(kind + ' ', link);
+    results.append(item);
+
+    if (++count == 200) {
+      break;
+    }
+  }
+});
+";
+
+// A type with a page, pages are named by number since obfuscated names often only differ in case
+struct Page {
+  id: usize,
+  label: String,
+  short_label: String,
+  kind: ClassKind,
+  parent: Option<String>
+}
+
+// Image, full name and how many types of that name the image defined before, the same name can be defined in
+// several images and even twice in one
+type PageKey = (String, String, usize);
+
+#[derive(Default)]
+struct Site {
+  pages: HashMap<PageKey, Page>,
+  by_class: HashMap<*const Class, PageKey>,
+  // The first page of every name, for names used in an image that doesn't define them
+  by_name: HashMap<String, PageKey>,
+  derived: HashMap<PageKey, Vec<PageKey>>,
+  namespaces: Vec<(String, NamespaceTree)>
+}
+
+impl Site {
+  // Page of a type named in an image, the image's own first definition wins over the ones in other images
+  fn resolve(&self, image: &str, name: &str) -> Option<(&PageKey, &Page)> {
+    self.pages.get_key_value(&(image.to_string(), name.to_string(), 0))
+      .or_else(|| self.by_name.get(name).and_then(|key| self.pages.get_key_value(key)))
+  }
+
+  // Parents are resolved once every page is known, they're often defined in a later image
+  fn page_of(&self, class: &Class) -> (&PageKey, &Page) {
+    let key = &self.by_class[&(class as *const Class)];
+    (key, &self.pages[key])
+  }
+
+  fn link_derived(&mut self) {
+    let mut derived: HashMap<PageKey, Vec<PageKey>> = HashMap::new();
+
+    for (key, page) in &self.pages {
+      if let Some((parent_key, _)) = page.parent.as_ref().and_then(|parent| self.resolve(&key.0, parent)) {
+        derived.entry(parent_key.clone()).or_default().push(key.clone());
+      }
+    }
+
+    for keys in derived.values_mut() {
+      keys.sort_by_key(|key| self.pages[key].id);
+    }
+
+    self.derived = derived;
+  }
+}
+
+#[derive(Default)]
+struct NamespaceTree {
+  children: BTreeMap<String, NamespaceTree>,
+  types: Vec<PageKey>
+}
+
+impl NamespaceTree {
+  fn insert(&mut self, namespace: &str, key: PageKey) {
+    let mut tree = self;
+
+    for segment in namespace.split('.').filter(|segment| !segment.is_empty()) {
+      tree = tree.children.entry(segment.to_string()).or_default();
+    }
+
+    tree.types.push(key);
+  }
+}
+
+fn escape(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn collect_pages(class: &Class, image: &Image, namespace: &str, site: &mut Site, tree: &mut NamespaceTree) {
+  let name = class.full_name();
+  let prefix = &name[..name.len() - class.name.len()];
+  let label = format!("{}{}", prefix, class.display_name());
+  let short_label = label.strip_prefix(namespace).and_then(|label| label.strip_prefix('.')).unwrap_or(&label).to_string();
+
+  let parent = class.parent.as_ref().and_then(TypeName::definition_name);
+  let mut key = (image.name.clone(), name.clone(), 0);
+
+  while site.pages.contains_key(&key) {
+    key.2 += 1;
+  }
+
+  let id = site.pages.len();
+  site.pages.insert(key.clone(), Page { id, label, short_label, kind: class.kind, parent });
+  site.by_name.entry(name).or_insert_with(|| key.clone());
+  site.by_class.insert(class, key.clone());
+  tree.insert(namespace, key);
+
+  for nested_type in &class.nested_types {
+    collect_pages(nested_type, image, namespace, site, tree);
+  }
+}
+
+fn page_link(page: &Page, text: &str) -> String {
+  format!("<a href=\"{}.html\" title=\"{}\">{}</a>", page.id, escape(&page.label), escape(text))
+}
+
+// A type named in the given image, linked to its page if it has one
+fn link(site: &Site, image: &str, name: &str, text: &str) -> String {
+  match site.resolve(image, name) {
+    Some((_, page)) => page_link(page, text),
+    None => format!("<span title=\"{}\">{}</span>", escape(name), escape(text))
+  }
+}
+
+// Type as written in C#, with every class that has a page linked to it
+fn type_html(site: &Site, image: &str, type_name: &TypeName) -> String {
+  match type_name {
+    TypeName::Class { name, .. } => link(site, image, name, name.rsplit('.').next().unwrap_or(name)),
+    TypeName::GenericInstance { name, arguments, .. } => {
+      let arguments: Vec<String> = arguments.iter().map(|argument| type_html(site, image, argument)).collect();
+
+      if !name.contains('`') {
+        return format!("{}&lt;{}&gt;", link(site, image, name, name.rsplit('.').next().unwrap_or(name)), arguments.join(", "));
+      }
+
+      // Written from the first generic segment on, e.g. Outer<int>.Inner<string>, every segment links to the type
+      // it names and takes the arguments its arity says
+      let segments = nested_arguments(name, &arguments);
+      let first = segments.iter().position(|(segment, _)| segment.contains('`')).unwrap_or(0);
+      let mut definition = String::new();
+      let mut written = vec![];
+
+      for (i, (segment, taken)) in segments.into_iter().enumerate() {
+        if i > 0 {
+          definition.push('.');
+        }
+
+        definition.push_str(segment);
+
+        if i >= first {
+          let text = link(site, image, &definition, &strip_arity(segment));
+          written.push(if segment.contains('`') { format!("{}&lt;{}&gt;", text, taken.join(", ")) } else { text });
+        }
+      }
+
+      written.join(".")
+    },
+    TypeName::Array { element, rank } => format!("{}[{}]", type_html(site, image, element), ",".repeat(rank.saturating_sub(1) as usize)),
+    TypeName::Pointer(element) => format!("{}*", type_html(site, image, element)),
+    TypeName::ByRef(element) => type_html(site, image, element),
+    _ => escape(&type_name.to_string())
+  }
+}
+
+// Parents from the root of the hierarchy down to the type, e.g. object > Component > MonoBehaviour,
+// each parent is looked up in the image of the type deriving from it
+fn write_inheritance(site: &Site, key: &PageKey, output: &mut String) -> Result<(), Box<dyn Error>> {
+  let mut crumbs = vec![];
+  let mut visited = vec![key];
+  let mut current = key;
+
+  while let Some(parent) = &site.pages[current].parent {
+    match site.resolve(&current.0, parent) {
+      Some((parent_key, _)) if visited.contains(&parent_key) => break,
+      Some((parent_key, page)) => {
+        crumbs.push(page_link(page, &page.label));
+        visited.push(parent_key);
+        current = parent_key;
+      },
+      None => {
+        crumbs.push(escape(&strip_arity(parent)));
+        break;
+      }
+    }
+  }
+
+  if crumbs.is_empty() {
+    return Ok(());
+  }
+
+  crumbs.reverse();
+  crumbs.push(format!("<b>{}</b>", escape(&site.pages[key].label)));
+
+  writeln!(output, "<p class=\"inheritance\">{}</p>", crumbs.join(" &rsaquo; "))?;
+
+  Ok(())
+}
+
+fn write_list(title: &str, items: &[String], output: &mut String) -> Result<(), Box<dyn Error>> {
+  if items.is_empty() {
+    return Ok(());
+  }
+
+  writeln!(output, "<h2>{}</h2>\n<ul>", title)?;
+
+  for item in items {
+    writeln!(output, "<li>{}</li>", item)?;
+  }
+
+  writeln!(output, "</ul>")?;
+
+  Ok(())
+}
+
+fn write_members(site: &Site, image: &str, class: &Class, output: &mut String) -> Result<(), Box<dyn Error>> {
+  if !class.fields.is_empty() {
+    writeln!(output, "<h2>Fields</h2>\n<table>")?;
+
+    for field in &class.fields {
+      let detail = if field.flags & (FIELD_ATTRIBUTE_LITERAL | FIELD_ATTRIBUTE_HAS_DEFAULT) != 0 || class.kind == ClassKind::Enum {
+        field.value.as_ref().map(|value| format!("= {}", escape(&value.to_string()))).unwrap_or_default()
+      }
+      else if field.thread_static {
+        "thread static".to_string()
+      }
+      else {
+        format!("0x{:x}", field.offset)
+      };
+
+      writeln!(output, "<tr><td class=\"modifiers\">{}</td><td>{}</td><td>{}</td><td class=\"address\">{}</td></tr>",
+        write_field_modifiers(field.flags).trim_end(), type_html(site, image, &field.type_name), escape(&field.name), detail)?;
+    }
+
+    writeln!(output, "</table>")?;
+  }
+
+  if !class.properties.is_empty() {
+    writeln!(output, "<h2>Properties</h2>\n<table>")?;
+
+    for property in &class.properties {
+      let accessors: Vec<&str> = [("get;", &property.getter), ("set;", &property.setter)].iter()
+        .filter(|(_, accessor)| accessor.is_some())
+        .map(|(name, _)| *name)
+        .collect();
+
+      writeln!(output, "<tr><td>{}</td><td>{}</td><td>{{ {} }}</td></tr>", type_html(site, image, &property.type_name), escape(&property.name), accessors.join(" "))?;
+    }
+
+    writeln!(output, "</table>")?;
+  }
+
+  if !class.methods.is_empty() {
+    writeln!(output, "<h2>Methods</h2>\n<table>")?;
+
+    for method in &class.methods {
+      let params: Vec<String> = method.params.iter().enumerate()
+        .map(|(i, param)| format!("{}{} {}", escape(&write_param_modifiers(param)), type_html(site, image, &param.type_name), escape(&write_param_name(&param.name, i))))
+        .collect();
+
+      let address = method.code.rva.map(|rva| format!("RVA 0x{:x}", rva)).unwrap_or_else(|| method.code.kind.as_str().to_string());
+
+      writeln!(output, "<tr><td class=\"modifiers\">{}</td><td>{}{}</td><td>{}{}({})</td><td class=\"address\">{}</td></tr>",
+        write_method_modifiers(method.flags).trim_end(),
+        if method.return_byref { "ref " } else { "" },
+        type_html(site, image, &method.return_type),
+        escape(&method.name),
+        escape(&write_generic_params(&method.generic_params)),
+        params.join(", "),
+        address)?;
+    }
+
+    writeln!(output, "</table>")?;
+  }
+
+  Ok(())
+}
+
+fn write_page(site: &Site, image: &Image, class: &Class) -> Result<usize, Box<dyn Error>> {
+  let (key, page) = site.page_of(class);
+
+  let mut output = String::new();
+
+  writeln!(output, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"../style.css\">\n</head>\n<body>", escape(&page.label))?;
+  writeln!(output, "<nav><a href=\"../index.html\">Index</a> &rsaquo; {} &rsaquo; {}</nav>", escape(&image.name), escape(&class.namespace))?;
+  writeln!(output, "<h1><span class=\"kind\">{}</span> {}</h1>", class.kind.as_str(), escape(&page.label))?;
+
+  write_inheritance(site, key, &mut output)?;
+
+  if let Some(declaring_type) = &class.declaring_type {
+    writeln!(output, "<p>Nested in {}</p>", link(site, &image.name, declaring_type, declaring_type))?;
+  }
+
+  let interfaces: Vec<String> = class.interfaces.iter().map(|interface| type_html(site, &image.name, interface)).collect();
+  write_list("Interfaces", &interfaces, &mut output)?;
+
+  let derived: Vec<String> = site.derived.get(key).into_iter().flatten().map(|derived| {
+    let derived_page = &site.pages[derived];
+    page_link(derived_page, &derived_page.label)
+  }).collect();
+  write_list("Derived types", &derived, &mut output)?;
+
+  let nested: Vec<String> = class.nested_types.iter().map(|nested_type| {
+    let (_, nested_page) = site.page_of(nested_type);
+    page_link(nested_page, &nested_page.short_label)
+  }).collect();
+  write_list("Nested types", &nested, &mut output)?;
+
+  write_members(site, &image.name, class, &mut output)?;
+
+  writeln!(output, "</body>\n</html>")?;
+
+  fs::write(Path::new("html/types").join(format!("{}.html", page.id)), output)?;
+
+  let mut page_count = 1;
+
+  for nested_type in &class.nested_types {
+    page_count += write_page(site, image, nested_type)?;
+  }
+
+  Ok(page_count)
+}
+
+fn write_tree(site: &Site, tree: &NamespaceTree, output: &mut impl Write) -> Result<(), Box<dyn Error>> {
+  for (segment, child) in &tree.children {
+    writeln!(output, "<details><summary>{}</summary>", escape(segment))?;
+    write_tree(site, child, output)?;
+    writeln!(output, "</details>")?;
+  }
+
+  if !tree.types.is_empty() {
+    writeln!(output, "<ul>")?;
+
+    for key in &tree.types {
+      let page = &site.pages[key];
+      writeln!(output, "<li><span class=\"kind\">{}</span> <a href=\"types/{}.html\">{}</a></li>", page.kind.as_str(), page.id, escape(&page.short_label))?;
+    }
+
+    writeln!(output, "</ul>")?;
+  }
+
+  Ok(())
+}
+
+fn write_index(site: &Site) -> Result<(), Box<dyn Error>> {
+  let mut output = Sink::create("html/index.html", Compression::None)?;
+
+  writeln!(output, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>honkai-dumper</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>")?;
+  writeln!(output, "<h1>{} types</h1>", site.pages.len())?;
+  writeln!(output, "<input id=\"search\" type=\"search\" placeholder=\"Search types\" autofocus>\n<ul id=\"results\"></ul>")?;
+
+  for (image_name, tree) in &site.namespaces {
+    writeln!(output, "<details><summary>{}</summary>", escape(image_name))?;
+    write_tree(site, tree, &mut output)?;
+    writeln!(output, "</details>")?;
+  }
+
+  writeln!(output, "<script src=\"search-index.js\"></script>\n<script src=\"search.js\"></script>\n</body>\n</html>")?;
+  output.finish()?;
+
+  // A script rather than JSON, browsers don't let pages opened from disk fetch other files
+  let mut entries: Vec<&Page> = site.pages.values().collect();
+  entries.sort_by_key(|page| page.id);
+
+  let entries: Vec<_> = entries.iter().map(|page| json!([page.label, page.id, page.kind.as_str()])).collect();
+
+  let mut search_index = Sink::create("html/search-index.js", Compression::None)?;
+  writeln!(search_index, "const TYPES = {};", json!(entries))?;
+  search_index.finish()?;
+
+  fs::write("html/search.js", SEARCH_SCRIPT)?;
+  fs::write("html/style.css", STYLE)?;
+
+  Ok(())
+}
+
+// Writes a static site to the html folder with a page per type, it's opened from index.html without a server
+pub fn dump(game: &Game) -> Result<(), Box<dyn Error>> {
+  // Every page has to be known before the first one is written to link to the ones after it
+  let mut site = Site::default();
+
+  for ImageClasses { image, classes } in &game.images {
+    let mut tree = NamespaceTree::default();
+
+    for class in classes {
+      collect_pages(class, image, &class.namespace, &mut site, &mut tree);
+    }
+
+    site.namespaces.push((image.name.clone(), tree));
+  }
+
+  site.link_derived();
+
+  // Page numbers change between dumps, old pages would be left pointing to the wrong types
+  if Path::new("html/types").exists() {
+    fs::remove_dir_all("html/types")?;
+  }
+
+  fs::create_dir_all("html/types")?;
+
+  let mut page_count = 0;
+
+  for ImageClasses { image, classes } in &game.images {
+    for class in classes {
+      page_count += write_page(&site, image, class)?;
+    }
+  }
+
+  write_index(&site)?;
+
+  println!("{} type pages saved to the html folder", page_count);
+
+  Ok(())
+}
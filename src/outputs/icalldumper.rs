@@ -0,0 +1,34 @@
+use std::{collections::HashSet, error::Error};
+use serde_json::json;
+
+use crate::{model::{game::Game, icall::InternalCall}, outputs::sink::{Compression, JsonWriter, Sink}};
+
+pub fn dump(game: &Game, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let calls: Vec<&InternalCall> = game.all_classes().into_iter()
+    .flat_map(|(_, class)| &class.methods)
+    .filter_map(|method| method.internal_call.as_ref())
+    .collect();
+
+  let file_name = compression.file_name("icalls.json");
+  let mut sink = Sink::create(&file_name, compression)?;
+  let mut output = JsonWriter::object(&mut sink)?;
+
+  // Keyed by signature, overloads can be registered under the same name
+  let mut written = HashSet::new();
+
+  for call in calls.iter().filter(|call| written.insert(&call.signature)) {
+    output.entry(&call.signature, &json!({
+      "name": call.name,
+      "module": call.module,
+      "rva": call.rva.map(|rva| format!("0x{:x}", rva))
+    }))?;
+  }
+
+  output.finish()?;
+  sink.finish()?;
+
+  let resolved = calls.iter().filter(|call| call.rva.is_some()).count();
+  println!("{} internal calls found ({} resolved) and saved to {}", calls.len(), resolved, file_name);
+
+  Ok(())
+}
@@ -1,83 +1,156 @@
-use std::{collections::HashMap, error::Error, ffi::c_void, fs::File, ptr::null};
+use std::{collections::HashMap, error::Error};
 use serde_json::json;
 
-use crate::il2cpp::api::{self, Il2CppApi};
+use crate::{il2cpp::api::{self, Il2CppApi}, model::{class::{CodeKind, MethodCode, Param}, game::Game}, outputs::sink::{Compression, JsonWriter, Sink}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MethodsFormat {
+  // { "Ns.Class::Method": "0x.." }, overloads get a _1, _2 suffix in the order of their signatures,
+  // only methods compiled into GameAssembly are listed so every value is an RVA in it
+  Names,
+  // { "Ns.Class::Method(int, string)": "0x.." }, same methods as Names
+  Signatures,
+  // [{ "name", "signature", "kind", "module", "rva", "va", "flags", "image" }], including methods without code
+  Structured
+}
 
-fn verify_pointer(il2cpp: &Il2CppApi, pointer: usize) -> bool {
-  (pointer > il2cpp.game_assembly.handle as usize) &&
-    (pointer < il2cpp.game_assembly.handle as usize + il2cpp.game_assembly.size)
+struct Entry {
+  name: String,
+  signature: String,
+  code: MethodCode,
+  flags: i32,
+  image: String
 }
 
-pub fn dump() -> Result<(), Box<dyn Error>> {
-  let il2cpp = api::get_il2cpp_api()?;
-  
-  let mut name_map: HashMap<String, usize> = HashMap::new();
-  let mut duplicates: HashMap<String, u32> = HashMap::new();
+impl Entry {
+  // Code outside GameAssembly is left to the structured format, which has the module it's in
+  fn address(&self) -> Option<String> {
+    self.code.compiled_rva().map(|rva| format!("0x{:x}", rva))
+  }
+}
+
+// Parameter list as written in C#, e.g. (int, ref string), which is what tells overloads apart
+fn method_params(params: &[Param]) -> String {
+  let params: Vec<String> = params.iter()
+    .map(|param| format!("{}{}", if param.byref { "ref " } else { "" }, param.type_name))
+    .collect();
+
+  format!("({})", params.join(", "))
+}
 
-  let domain = il2cpp.domain_get()?;
+fn collect_entries(il2cpp: &Il2CppApi, game: &Game) -> Result<Vec<Entry>, Box<dyn Error>> {
+  let mut entries = vec![];
 
-  let assembly_count: usize = 0;
-  let assemblies = il2cpp.domain_get_assemblies(domain, &assembly_count)?;
+  for (image, class) in game.all_classes() {
+    let class_name = class.full_name();
 
-  for i in 0..assembly_count {
-    let assembly = unsafe { *assemblies.offset(i as isize) };
+    for method in &class.methods {
+      let name = format!("{}::{}", class_name, method.name);
 
-    if assembly.is_null() {
-      continue;
+      entries.push(Entry {
+        signature: format!("{}{}", name, method_params(&method.params)),
+        name,
+        code: method.code.clone(),
+        flags: method.flags,
+        image: image.name.clone()
+      });
     }
+  }
 
-    let image = il2cpp.assembly_get_image(assembly)?;
-    let class_count = il2cpp.image_get_class_count(image)?;
+  // Instantiations have their own code, their names already carry the type arguments
+  for instance in game.instances.values().flatten() {
+    let declaring_class = unsafe { (*instance.handle).klass };
+    let image = il2cpp.image_get_name(il2cpp.class_get_image(declaring_class)?)?;
+
+    entries.push(Entry {
+      signature: format!("{}{}", instance.name, method_params(&instance.params)),
+      name: instance.name.clone(),
+      code: instance.code.clone(),
+      flags: unsafe { (*instance.handle).flags } as i32,
+      image
+    });
+  }
 
-    for j in 0..class_count {
-      let class = il2cpp.image_get_class(image, j)?;
+  // The runtime's method order changes between game versions, sorting keeps overload numbering stable
+  entries.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.signature.cmp(&b.signature)).then_with(|| a.code.rva.cmp(&b.code.rva)));
 
-      if class.is_null() {
-        continue;
-      }
+  Ok(entries)
+}
 
-      let class_name = il2cpp.class_get_name(class)?;
-      let mut class_namespace = il2cpp.class_get_namespace(class)?;
+// Appends _1, _2 to keys that are already taken, in the order the entries were sorted in
+fn unique_keys<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+  let mut taken: HashMap<&String, u32> = HashMap::new();
+  let mut unique = vec![];
 
-      if !class_namespace.is_empty() {
-        class_namespace.push('.');
-      }
+  for key in keys {
+    let count = taken.entry(key).or_insert(0);
 
-      let method_iter: *const c_void = null();
+    unique.push(if *count == 0 { key.clone() } else { format!("{}_{}", key, count) });
+    *count += 1;
+  }
+
+  unique
+}
+
+pub fn dump(game: &Game, format: MethodsFormat, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let il2cpp = api::get_il2cpp_api()?;
+  let entries = collect_entries(il2cpp, game)?;
+
+  let file_name = compression.file_name("methods.json");
+  let mut sink = Sink::create(&file_name, compression)?;
 
-      while let Some(method_info) = il2cpp.class_get_methods(class, &method_iter)? {
-        let pointer = unsafe { (*method_info).method_pointer as usize };
+  // Only methods with an RVA in GameAssembly make it into the maps
+  let written = match format {
+    MethodsFormat::Names | MethodsFormat::Signatures => {
+      let addressed: Vec<(&Entry, String)> = entries.iter()
+        .filter_map(|entry| entry.address().map(|address| (entry, address)))
+        .collect();
 
-        if !verify_pointer(il2cpp, pointer) {
-          continue;
-        }
+      let keys = match format {
+        MethodsFormat::Names => unique_keys(addressed.iter().map(|(entry, _)| &entry.name)),
+        _ => unique_keys(addressed.iter().map(|(entry, _)| &entry.signature))
+      };
 
-        let method_name = il2cpp.method_get_name(method_info)?;
-        let description = format!("{}{}::{}", class_namespace, class_name, method_name);
+      let mut output = JsonWriter::object(&mut sink)?;
 
-        let unique_description = if name_map.contains_key(&description) {
-          let count = duplicates.entry(description.to_string()).or_insert(0);
-          *count += 1;
-          format!("{}_{}", description, count)
-        } else {
-          description
-        };
+      for (key, (_, address)) in keys.iter().zip(&addressed) {
+        output.entry(key, &json!(address))?;
+      }
 
-        name_map.insert(unique_description, pointer - il2cpp.game_assembly.handle as usize);
+      output.finish()?;
+      addressed.len()
+    },
+    MethodsFormat::Structured => {
+      let mut output = JsonWriter::array(&mut sink)?;
+
+      for entry in &entries {
+        output.element(&json!({
+          "name": entry.name,
+          "signature": entry.signature,
+          "kind": entry.code.kind.as_str(),
+          "module": entry.code.module,
+          "rva": entry.code.rva.map(|rva| format!("0x{:x}", rva)),
+          "va": entry.code.rva.filter(|_| entry.code.kind == CodeKind::Compiled).map(|rva| format!("0x{:x}", rva + game.image_base)),
+          "flags": entry.flags,
+          "image": entry.image
+        }))?;
       }
+
+      output.finish()?;
+      entries.len()
     }
-  }
+  };
 
-  let mut output = json!({});
+  sink.finish()?;
 
-  for (name, address) in &name_map {
-    output[name] = json!(format!("0x{:x}", address));
-  }
+  println!("{} methods found, {} saved to {}", entries.len(), written, file_name);
 
-  let mut file = File::create("methods.json").unwrap();
-  serde_json::to_writer_pretty(&mut file, &output).unwrap();
+  let kinds = [CodeKind::Compiled, CodeKind::External, CodeKind::Abstract, CodeKind::UnresolvedGeneric, CodeKind::NoCode];
 
-  println!("{} valid methods found and saved to methods.json", name_map.len());
+  for kind in kinds {
+    let count = entries.iter().filter(|entry| entry.code.kind == kind).count();
+    println!("  {}: {}", kind.as_str(), count);
+  }
 
   Ok(())
-}
\ No newline at end of file
+}
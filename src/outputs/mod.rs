@@ -1,2 +1,13 @@
+pub mod assemblydumper;
 pub mod csdumper;
-pub mod methoddumper;
\ No newline at end of file
+pub mod graphdumper;
+pub mod htmldumper;
+pub mod icalldumper;
+pub mod methoddumper;
+pub mod scriptdumper;
+pub mod sink;
+pub mod sqldumper;
+pub mod stringdumper;
+pub mod typedumper;
+pub mod usagedumper;
+pub mod xrefdumper;
@@ -0,0 +1,165 @@
+use std::{collections::HashMap, error::Error};
+use serde_json::json;
+
+use crate::{il2cpp::{api::{self, Il2CppApi, Il2CppError}, constants::*, types::*}, model::{class::{MethodCode, Param}, game::Game, type_name::{mangle, TypeName, TypeStyle}, usage::{MetadataUsage, UsageKind}}, outputs::sink::{Compression, JsonWriter, Sink}};
+
+// C declaration in the form il2cpp generates it, e.g.
+// void RPG_Client_AutoScrollRect__SnapTo (RPG_Client_AutoScrollRect_o* __this, UnityEngine_GameObject_o* param0, const MethodInfo* method);
+fn method_signature(il2cpp: &Il2CppApi, method: *const MethodInfo, return_type: &TypeName, params: &[Param], name: &str) -> Result<String, Il2CppError> {
+  let mut signature_params = vec![];
+
+  if unsafe { (*method).flags } as i32 & METHOD_ATTRIBUTE_STATIC == 0 {
+    let class = unsafe { (*method).klass };
+    let this_type = TypeName::from_class(il2cpp, class)?.format(TypeStyle::C);
+    let pointer = if il2cpp.class_is_valuetype(class)? { "*" } else { "" };
+    signature_params.push(format!("{}{} __this", this_type, pointer));
+  }
+
+  for (i, param) in params.iter().enumerate() {
+    signature_params.push(format!("{} param{}", param.type_name.format(TypeStyle::C), i));
+  }
+
+  signature_params.push("const MethodInfo* method".to_string());
+
+  Ok(format!("{} {} ({});", return_type.format(TypeStyle::C), mangle(name), signature_params.join(", ")))
+}
+
+// Compiled methods and instances as (address, name, handle, return type, params), signatures are only built
+// when they're written
+type ScriptMethod<'a> = (usize, String, *const MethodInfo, &'a TypeName, &'a [Param]);
+
+fn script_methods(game: &Game, sorted: bool) -> Vec<ScriptMethod<'_>> {
+  let mut methods = vec![];
+
+  for (_, class) in game.all_classes() {
+    let class_name = class.full_name();
+
+    for method in &class.methods {
+      if let Some(rva) = method.code.compiled_rva() {
+        methods.push((rva, format!("{}$${}", class_name, method.name), method.handle, &method.return_type, &method.params[..]));
+      }
+    }
+  }
+
+  for instance in game.instances.values().flatten() {
+    if let Some(rva) = instance.code.compiled_rva() {
+      methods.push((rva, instance.name.replace("::", "$$"), instance.handle, &instance.return_type, &instance.params[..]));
+    }
+  }
+
+  // Instances come out of a hash map, so without sorting their order differs every run
+  if sorted {
+    methods.sort_by(|(rva, name, ..), (other_rva, other_name, ..)| (rva, name).cmp(&(other_rva, other_name)));
+  }
+
+  methods
+}
+
+// Code of every method and instance the game has, by handle, so references agree with the methods listed
+fn method_codes(game: &Game) -> HashMap<*const MethodInfo, &MethodCode> {
+  let mut codes = HashMap::new();
+
+  for (_, class) in game.all_classes() {
+    for method in &class.methods {
+      codes.insert(method.handle, &method.code);
+    }
+  }
+
+  for instance in game.instances.values().flatten() {
+    codes.insert(instance.handle, &instance.code);
+  }
+
+  codes
+}
+
+// Writes script.json in the format Il2CppDumper uses, so its IDA and Ghidra scripts can be used to name everything
+pub fn dump(game: &Game, usages: &[MetadataUsage], sorted: bool, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let il2cpp = api::get_il2cpp_api()?;
+
+  let methods = script_methods(game, sorted);
+  let codes = method_codes(game);
+
+  let file_name = compression.file_name("script.json");
+  let mut sink = Sink::create(&file_name, compression)?;
+  let mut output = JsonWriter::object(&mut sink)?;
+
+  let mut entries = output.array_entry("ScriptMethod")?;
+
+  for (rva, name, handle, return_type, params) in &methods {
+    entries.element(&json!({
+      "Address": rva,
+      "Name": name,
+      "Signature": method_signature(il2cpp, *handle, return_type, params, name)?
+    }))?;
+  }
+
+  entries.finish()?;
+
+  // Names follow Il2CppDumper's, e.g. Ns.Class_TypeInfo or Method$Ns.Class::Method()
+  let mut entries = output.array_entry("ScriptString")?;
+  let mut string_count = 0;
+
+  for usage in usages {
+    if let UsageKind::StringLiteral(value) = &usage.kind {
+      entries.element(&json!({
+        "Address": usage.rva,
+        "Value": value
+      }))?;
+      string_count += 1;
+    }
+  }
+
+  entries.finish()?;
+
+  let mut entries = output.array_entry("ScriptMetadata")?;
+  let mut metadata_count = 0;
+
+  for usage in usages {
+    let entry = match &usage.kind {
+      UsageKind::TypeInfo(type_name) => json!({
+        "Address": usage.rva,
+        "Name": format!("{}_TypeInfo", type_name),
+        "Signature": format!("{}_c*", mangle(&type_name.format(TypeStyle::FullyQualified)))
+      }),
+      UsageKind::Il2CppType(type_name) => json!({
+        "Address": usage.rva,
+        "Name": format!("{}_var", type_name),
+        "Signature": "Il2CppType*"
+      }),
+      UsageKind::FieldInfo(name) => json!({
+        "Address": usage.rva,
+        "Name": format!("Field${}", name),
+        "Signature": "FieldInfo*"
+      }),
+      _ => continue
+    };
+
+    entries.element(&entry)?;
+    metadata_count += 1;
+  }
+
+  entries.finish()?;
+
+  let mut entries = output.array_entry("ScriptMetadataMethod")?;
+
+  for usage in usages {
+    if let UsageKind::MethodDef(method, name) | UsageKind::MethodRef(method, name) = &usage.kind {
+      entries.element(&json!({
+        "Address": usage.rva,
+        "Name": format!("Method${}()", name),
+        "MethodAddress": codes.get(method).and_then(|code| code.compiled_rva()).unwrap_or(0)
+      }))?;
+      metadata_count += 1;
+    }
+  }
+
+  entries.finish()?;
+
+  output.array_entry("Addresses")?.finish()?;
+  output.finish()?;
+  sink.finish()?;
+
+  println!("{} methods, {} strings and {} metadata references saved to {}", methods.len(), string_count, metadata_count, file_name);
+
+  Ok(())
+}
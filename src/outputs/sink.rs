@@ -0,0 +1,235 @@
+use std::{fs::File, io::{self, BufWriter, Write}, path::Path};
+use flate2::{write::GzEncoder, Compression as Level};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+  None,
+  // Gzip with a .gz suffix, flushing keeps everything written so far decompressable
+  Gzip
+}
+
+impl Compression {
+  pub fn file_name(&self, name: &str) -> String {
+    match self {
+      Compression::None => name.to_string(),
+      Compression::Gzip => format!("{}.gz", name)
+    }
+  }
+}
+
+// Buffered output file, dumps are written to it piece by piece instead of being built in memory first
+pub enum Sink {
+  Plain(BufWriter<File>),
+  Gzip(GzEncoder<BufWriter<File>>)
+}
+
+impl Sink {
+  // The path is used as is, see Compression::file_name for the name to give it
+  pub fn create(path: impl AsRef<Path>, compression: Compression) -> io::Result<Sink> {
+    let file = BufWriter::new(File::create(path)?);
+
+    Ok(match compression {
+      Compression::None => Sink::Plain(file),
+      Compression::Gzip => Sink::Gzip(GzEncoder::new(file, Level::default()))
+    })
+  }
+
+  // Writes out what's still buffered and the gzip trailer, dropping the sink would ignore any error doing so
+  pub fn finish(self) -> io::Result<()> {
+    match self {
+      Sink::Plain(mut file) => file.flush(),
+      Sink::Gzip(encoder) => encoder.finish()?.flush()
+    }
+  }
+}
+
+impl Write for Sink {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    match self {
+      Sink::Plain(file) => file.write(buf),
+      Sink::Gzip(encoder) => encoder.write(buf)
+    }
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    match self {
+      Sink::Plain(file) => file.flush(),
+      Sink::Gzip(encoder) => encoder.flush()
+    }
+  }
+}
+
+// Writes a JSON object or array an entry at a time, laid out the way serde_json's pretty printer would,
+// so a dump never has to hold the whole document as a serde_json::Value
+pub struct JsonWriter<'a, W: Write> {
+  output: &'a mut W,
+  close: &'static [u8],
+  depth: usize,
+  count: usize
+}
+
+impl<'a, W: Write> JsonWriter<'a, W> {
+  pub fn object(output: &'a mut W) -> io::Result<JsonWriter<'a, W>> {
+    JsonWriter::open(output, b"{", b"}", 0)
+  }
+
+  pub fn array(output: &'a mut W) -> io::Result<JsonWriter<'a, W>> {
+    JsonWriter::open(output, b"[", b"]", 0)
+  }
+
+  pub fn entry(&mut self, key: &str, value: &Value) -> io::Result<()> {
+    self.key(key)?;
+    self.value(value)
+  }
+
+  pub fn element(&mut self, value: &Value) -> io::Result<()> {
+    self.separate()?;
+    self.value(value)
+  }
+
+  // An array under the key that's written an element at a time as well, it has to be finished before this one goes on
+  pub fn array_entry(&mut self, key: &str) -> io::Result<JsonWriter<'_, W>> {
+    self.key(key)?;
+    JsonWriter::open(&mut *self.output, b"[", b"]", self.depth + 1)
+  }
+
+  pub fn object_entry(&mut self, key: &str) -> io::Result<JsonWriter<'_, W>> {
+    self.key(key)?;
+    JsonWriter::open(&mut *self.output, b"{", b"}", self.depth + 1)
+  }
+
+  // An object element of this array, written an entry at a time
+  pub fn object_element(&mut self) -> io::Result<JsonWriter<'_, W>> {
+    self.separate()?;
+    JsonWriter::open(&mut *self.output, b"{", b"}", self.depth + 1)
+  }
+
+  pub fn finish(mut self) -> io::Result<()> {
+    if self.count > 0 {
+      self.newline(self.depth)?;
+    }
+
+    self.output.write_all(self.close)
+  }
+
+  fn open(output: &'a mut W, open: &[u8], close: &'static [u8], depth: usize) -> io::Result<JsonWriter<'a, W>> {
+    output.write_all(open)?;
+    Ok(JsonWriter { output, close, depth, count: 0 })
+  }
+
+  fn key(&mut self, key: &str) -> io::Result<()> {
+    self.separate()?;
+    serde_json::to_writer(&mut *self.output, key)?;
+    self.output.write_all(b": ")
+  }
+
+  fn separate(&mut self) -> io::Result<()> {
+    if self.count > 0 {
+      self.output.write_all(b",")?;
+    }
+
+    self.count += 1;
+    self.newline(self.depth + 1)
+  }
+
+  fn newline(&mut self, depth: usize) -> io::Result<()> {
+    self.output.write_all(b"\n")?;
+
+    for _ in 0..depth {
+      self.output.write_all(b"  ")?;
+    }
+
+    Ok(())
+  }
+
+  // Only the entry itself is formatted in memory, its lines are indented to the depth of the entry
+  fn value(&mut self, value: &Value) -> io::Result<()> {
+    let formatted = serde_json::to_vec_pretty(value)?;
+
+    for (i, line) in formatted.split(|&byte| byte == b'\n').enumerate() {
+      if i > 0 {
+        self.newline(self.depth + 1)?;
+      }
+
+      self.output.write_all(line)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn json_writer_matches_the_pretty_printer() {
+    let entries = [("a", json!("0x10")), ("b\"", json!({ "name": "x", "list": [1, 2] })), ("c", json!([]))];
+
+    let mut output = vec![];
+    let mut writer = JsonWriter::object(&mut output).unwrap();
+
+    for (key, value) in &entries {
+      writer.entry(key, value).unwrap();
+    }
+
+    writer.finish().unwrap();
+
+    let expected = serde_json::to_vec_pretty(&json!({ "a": "0x10", "b\"": { "name": "x", "list": [1, 2] }, "c": [] })).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), String::from_utf8(expected).unwrap());
+  }
+
+  #[test]
+  fn nested_json_writers_match_the_pretty_printer() {
+    let mut output = vec![];
+    let mut writer = JsonWriter::object(&mut output).unwrap();
+
+    writer.entry("partial", &json!(true)).unwrap();
+
+    let mut literals = writer.array_entry("literals").unwrap();
+    literals.element(&json!({ "index": 1, "value": "a" })).unwrap();
+    literals.element(&json!({ "index": 2, "value": null })).unwrap();
+    literals.finish().unwrap();
+
+    writer.array_entry("empty").unwrap().finish().unwrap();
+    writer.finish().unwrap();
+
+    let expected = "{\n  \"partial\": true,\n  \"literals\": [\n    {\n      \"index\": 1,\n      \"value\": \"a\"\n    },\n    {\n      \"index\": 2,\n      \"value\": null\n    }\n  ],\n  \"empty\": []\n}";
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+  }
+
+  #[test]
+  fn object_elements_match_the_pretty_printer() {
+    let mut output = vec![];
+    let mut writer = JsonWriter::array(&mut output).unwrap();
+
+    for index in 0..2 {
+      let mut image = writer.object_element().unwrap();
+      image.entry("index", &json!(index)).unwrap();
+
+      let mut types = image.array_entry("types").unwrap();
+      types.element(&json!({ "name": "A" })).unwrap();
+      types.finish().unwrap();
+      image.finish().unwrap();
+    }
+
+    writer.finish().unwrap();
+
+    let expected = serde_json::to_vec_pretty(&json!([
+      { "index": 0, "types": [{ "name": "A" }] },
+      { "index": 1, "types": [{ "name": "A" }] }
+    ])).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), String::from_utf8(expected).unwrap());
+  }
+
+  #[test]
+  fn json_writer_writes_empty_containers() {
+    let mut output = vec![];
+    JsonWriter::array(&mut output).unwrap().finish().unwrap();
+    JsonWriter::object(&mut output).unwrap().finish().unwrap();
+
+    assert_eq!(output, b"[]{}");
+  }
+}
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, error::Error, fs, path::Path};
+use rusqlite::{params, Connection, Transaction};
+
+use crate::{il2cpp::constants::*, model::{class::Class, game::{Game, ImageClasses}, type_name::{TypeName, TypeStyle}}};
+
+const SCHEMA: &str = "
+  CREATE TABLE images (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL
+  );
+
+  CREATE TABLE types (
+    id INTEGER PRIMARY KEY,
+    image_id INTEGER NOT NULL REFERENCES images(id),
+    declaring_type_id INTEGER REFERENCES types(id),
+    namespace TEXT NOT NULL,
+    name TEXT NOT NULL,
+    full_name TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    flags INTEGER NOT NULL,
+    instance_size INTEGER NOT NULL,
+    static_size INTEGER NOT NULL
+  );
+
+  CREATE TABLE fields (
+    id INTEGER PRIMARY KEY,
+    type_id INTEGER NOT NULL REFERENCES types(id),
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    flags INTEGER NOT NULL,
+    is_static INTEGER NOT NULL,
+    offset INTEGER,
+    value TEXT
+  );
+
+  CREATE TABLE methods (
+    id INTEGER PRIMARY KEY,
+    type_id INTEGER NOT NULL REFERENCES types(id),
+    name TEXT NOT NULL,
+    return_type TEXT NOT NULL,
+    flags INTEGER NOT NULL,
+    is_static INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    module TEXT,
+    rva INTEGER
+  );
+
+  CREATE TABLE params (
+    id INTEGER PRIMARY KEY,
+    method_id INTEGER NOT NULL REFERENCES methods(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    attrs INTEGER NOT NULL,
+    byref INTEGER NOT NULL,
+    default_value TEXT
+  );
+
+  -- parent and interface are fully qualified, e.g. System.Object, parent_id and interface_id point at the
+  -- generic definition for generic instances and are null for types outside the dump
+  CREATE TABLE inheritance (
+    type_id INTEGER NOT NULL REFERENCES types(id),
+    parent TEXT NOT NULL,
+    parent_id INTEGER REFERENCES types(id)
+  );
+
+  CREATE TABLE interfaces (
+    type_id INTEGER NOT NULL REFERENCES types(id),
+    interface TEXT NOT NULL,
+    interface_id INTEGER REFERENCES types(id)
+  );
+";
+
+const INDEXES: &str = "
+  CREATE INDEX types_name ON types(name);
+  CREATE INDEX types_full_name ON types(full_name);
+  CREATE INDEX fields_name ON fields(name);
+  CREATE INDEX fields_type ON fields(type);
+  CREATE INDEX fields_type_id ON fields(type_id);
+  CREATE INDEX methods_name ON methods(name);
+  CREATE INDEX methods_rva ON methods(rva);
+  CREATE INDEX methods_type_id ON methods(type_id);
+  CREATE INDEX params_type ON params(type);
+  CREATE INDEX params_method_id ON params(method_id);
+  CREATE INDEX inheritance_parent ON inheritance(parent);
+  CREATE INDEX inheritance_parent_id ON inheritance(parent_id);
+  CREATE INDEX interfaces_interface ON interfaces(interface);
+  CREATE INDEX interfaces_interface_id ON interfaces(interface_id);
+";
+
+// Type ids are handed out in the order the types are inserted in, before anything is inserted, so parents and
+// interfaces defined later can be linked right away.
+// Full names aren't unique across images, a name is looked up in the image using it first and in every image after that
+struct TypeIds {
+  by_image: HashMap<(usize, String), i64>,
+  by_name: HashMap<String, i64>
+}
+
+impl TypeIds {
+  fn collect(game: &Game) -> TypeIds {
+    let mut ids = TypeIds { by_image: HashMap::new(), by_name: HashMap::new() };
+
+    for (id, (image, class)) in game.all_classes().into_iter().enumerate() {
+      let id = id as i64 + 1;
+      let full_name = class.full_name();
+
+      ids.by_image.entry((image.index, full_name.clone())).or_insert(id);
+      ids.by_name.entry(full_name).or_insert(id);
+    }
+
+    ids
+  }
+
+  fn resolve(&self, image_index: usize, type_name: &TypeName) -> Option<i64> {
+    let name = type_name.definition_name()?;
+    self.by_image.get(&(image_index, name.clone())).or_else(|| self.by_name.get(&name)).copied()
+  }
+}
+
+fn insert_class(transaction: &Transaction, ids: &TypeIds, next_id: &mut i64, image_index: usize, declaring_type_id: Option<i64>, class: &Class) -> Result<usize, Box<dyn Error>> {
+  let type_id = *next_id;
+  *next_id += 1;
+
+  transaction.execute(
+    "INSERT INTO types (id, image_id, declaring_type_id, namespace, name, full_name, display_name, kind, flags, instance_size, static_size) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+    params![type_id, image_index as i64, declaring_type_id, class.namespace, class.name, class.full_name(), class.display_name(), class.kind.as_str(), class.flags, class.layout.instance_size, class.layout.static_size]
+  )?;
+
+  if let Some(parent) = &class.parent {
+    transaction.execute(
+      "INSERT INTO inheritance (type_id, parent, parent_id) VALUES (?1, ?2, ?3)",
+      params![type_id, parent.format(TypeStyle::FullyQualified), ids.resolve(image_index, parent)]
+    )?;
+  }
+
+  for interface in &class.interfaces {
+    transaction.execute(
+      "INSERT INTO interfaces (type_id, interface, interface_id) VALUES (?1, ?2, ?3)",
+      params![type_id, interface.format(TypeStyle::FullyQualified), ids.resolve(image_index, interface)]
+    )?;
+  }
+
+  for field in &class.fields {
+    transaction.execute(
+      "INSERT INTO fields (type_id, name, type, flags, is_static, offset, value) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+      params![
+        type_id,
+        field.name,
+        field.type_name.to_string(),
+        field.flags,
+        field.flags & FIELD_ATTRIBUTE_STATIC != 0,
+        (!field.thread_static).then_some(field.offset as i64),
+        field.value.as_ref().map(|value| value.to_string())
+      ]
+    )?;
+  }
+
+  for method in &class.methods {
+    transaction.execute(
+      "INSERT INTO methods (type_id, name, return_type, flags, is_static, kind, module, rva) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+      params![
+        type_id,
+        method.name,
+        method.return_type.to_string(),
+        method.flags,
+        method.flags & METHOD_ATTRIBUTE_STATIC != 0,
+        method.code.kind.as_str(),
+        method.code.module,
+        method.code.rva.map(|rva| rva as i64)
+      ]
+    )?;
+
+    let method_id = transaction.last_insert_rowid();
+
+    for (position, param) in method.params.iter().enumerate() {
+      transaction.execute(
+        "INSERT INTO params (method_id, position, name, type, attrs, byref, default_value) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![method_id, position as i64, param.name, param.type_name.to_string(), param.attrs, param.byref, param.default.as_ref().map(|value| value.to_string())]
+      )?;
+    }
+  }
+
+  let mut type_count = 1;
+
+  for nested_type in &class.nested_types {
+    type_count += insert_class(transaction, ids, next_id, image_index, Some(type_id), nested_type)?;
+  }
+
+  Ok(type_count)
+}
+
+// Writes dump.db with one table per kind of member, e.g.
+// SELECT types.full_name FROM fields JOIN types ON types.id = fields.type_id WHERE fields.type = 'UnityEngine.Vector3'
+pub fn dump(game: &Game) -> Result<(), Box<dyn Error>> {
+  if Path::new("dump.db").exists() {
+    fs::remove_file("dump.db")?;
+  }
+
+  let mut connection = Connection::open("dump.db")?;
+  connection.execute_batch(SCHEMA)?;
+
+  let ids = TypeIds::collect(game);
+
+  // A single transaction, committing every insert on its own would take minutes
+  let transaction = connection.transaction()?;
+  let mut type_count = 0;
+  let mut next_id = 1;
+
+  for ImageClasses { image, classes } in &game.images {
+    transaction.execute("INSERT INTO images (id, name) VALUES (?1, ?2)", params![image.index as i64, image.name])?;
+
+    for class in classes {
+      type_count += insert_class(&transaction, &ids, &mut next_id, image.index, None, class)?;
+    }
+  }
+
+  transaction.execute_batch(INDEXES)?;
+  transaction.commit()?;
+
+  println!("{} types found and saved to dump.db", type_count);
+
+  Ok(())
+}
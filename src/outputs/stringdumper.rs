@@ -0,0 +1,46 @@
+use std::error::Error;
+use serde_json::json;
+
+use crate::{il2cpp::api, model::usage::{MetadataUsage, PendingLiteral, UsageKind}, outputs::sink::{Compression, JsonWriter, Sink}};
+
+// Every string literal slot by its position in the slot table, the literals the game hasn't loaded yet are listed
+// with their index and slot address but without a value, which is what makes the file partial
+pub fn dump(usages: &[MetadataUsage], compression: Compression) -> Result<(), Box<dyn Error>> {
+  let il2cpp = api::get_il2cpp_api()?;
+  let pending = PendingLiteral::collect_all(il2cpp)?;
+
+  let mut literals: Vec<(usize, Option<u32>, Option<&str>, usize)> = usages.iter()
+    .filter_map(|usage| match &usage.kind {
+      UsageKind::StringLiteral(value) => Some((usage.slot, usage.index, Some(value.as_str()), usage.rva)),
+      _ => None
+    })
+    .chain(pending.iter().map(|literal| (literal.slot, Some(literal.index), None, literal.rva)))
+    .collect();
+
+  literals.sort_by_key(|&(slot, ..)| slot);
+
+  let file_name = compression.file_name("stringliteral.json");
+  let mut sink = Sink::create(&file_name, compression)?;
+  let mut output = JsonWriter::object(&mut sink)?;
+
+  output.entry("partial", &json!(!pending.is_empty()))?;
+  output.entry("note", &json!("index is the literal's index in the metadata, value is null for literals the game hasn't loaded before the dump"))?;
+
+  let mut entries = output.array_entry("literals")?;
+
+  for (_, index, value, rva) in &literals {
+    entries.element(&json!({
+      "index": index,
+      "value": value,
+      "address": format!("0x{:x}", rva)
+    }))?;
+  }
+
+  entries.finish()?;
+  output.finish()?;
+  sink.finish()?;
+
+  println!("{} string literals found ({} not loaded yet) and saved to {}", literals.len(), pending.len(), file_name);
+
+  Ok(())
+}
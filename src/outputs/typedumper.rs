@@ -0,0 +1,281 @@
+use std::{error::Error, fs, io::{self, Write}};
+use serde_json::{json, Map, Value};
+
+use crate::{model::{attribute::Attribute, class::{Accessor, Class, MethodCode}, flags::{self, FlagTable}, game::{Game, ImageClasses}, generic::{GenericInstance, GenericInstances, GenericParam}, type_name::TypeName}, outputs::sink::{Compression, JsonWriter, Sink}};
+
+// Bumped whenever a key is renamed, removed or changes meaning, adding keys keeps the version
+pub const SCHEMA_VERSION: u32 = 2;
+
+const SCHEMA: &str = include_str!("../../schema/types.schema.json");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypesFormat {
+  // types.json, a single document with every image and its types
+  Json,
+  // types.jsonl, a header line followed by one line per type, for consumers that stream the dump
+  JsonLines
+}
+
+fn dump_flags(flags: i32, table: FlagTable) -> Value {
+  let mut decoded = Map::new();
+
+  for (name, set) in flags::decode(flags, table) {
+    decoded.insert(name.to_string(), json!(set));
+  }
+
+  json!(decoded)
+}
+
+fn dump_attributes(attributes: &[Attribute]) -> Value {
+  let attributes: Vec<Value> = attributes.iter().map(|attribute| {
+    let mut arguments = Map::new();
+
+    for (name, value) in &attribute.arguments {
+      arguments.insert(name.clone(), value.to_json());
+    }
+
+    json!({
+      "type": attribute.type_name,
+      "constructor_rva": attribute.constructor_rva.map(|rva| format!("0x{:x}", rva)),
+      "arguments": arguments
+    })
+  }).collect();
+
+  json!(attributes)
+}
+
+fn dump_generic_params(params: &[GenericParam]) -> Value {
+  let params: Vec<Value> = params.iter().map(|param| {
+    json!({
+      "name": param.name,
+      "flags": param.flags,
+      "constraints": param.constraint_list()
+    })
+  }).collect();
+
+  json!(params)
+}
+
+fn dump_code(code: &MethodCode) -> Value {
+  json!({
+    "kind": code.kind.as_str(),
+    "module": code.module,
+    "rva": code.rva.map(|rva| format!("0x{:x}", rva))
+  })
+}
+
+fn dump_accessor(accessor: &Option<Accessor>) -> Value {
+  match accessor {
+    Some(accessor) => json!({
+      "name": accessor.name,
+      "flags": accessor.flags,
+      "decoded_flags": dump_flags(accessor.flags, flags::METHOD_FLAGS),
+      "rva": accessor.code.compiled_rva().map(|rva| format!("0x{:x}", rva)),
+      "code": dump_code(&accessor.code)
+    }),
+    None => Value::Null
+  }
+}
+
+fn dump_generic_instances(instances: Option<&Vec<GenericInstance>>) -> Value {
+  let instances: Vec<Value> = instances.into_iter().flatten().map(|instance| {
+    json!({
+      "name": instance.name,
+      "rva": instance.code.compiled_rva().map(|rva| format!("0x{:x}", rva)),
+      "code": dump_code(&instance.code)
+    })
+  }).collect();
+
+  json!(instances)
+}
+
+// Nested types are listed right after their declaring type rather than inside of it
+// Hands the class and every type nested in it to write one at a time
+fn dump_classes(class: &Class, instances: &GenericInstances, write: &mut impl FnMut(Value) -> io::Result<()>) -> io::Result<usize> {
+  write(dump_class(class, instances))?;
+
+  let mut count = 1;
+
+  for nested_type in &class.nested_types {
+    count += dump_classes(nested_type, instances, write)?;
+  }
+
+  Ok(count)
+}
+
+fn dump_class(class: &Class, instances: &GenericInstances) -> Value {
+  let fields: Vec<Value> = class.fields.iter().map(|field| {
+    json!({
+      "name": field.name,
+      "type": field.type_name.to_string(),
+      "flags": field.flags,
+      "decoded_flags": dump_flags(field.flags, flags::FIELD_FLAGS),
+      "offset": (!field.thread_static).then(|| format!("0x{:x}", field.offset)),
+      "thread_static": field.thread_static,
+      "address": field.address.map(|address| format!("0x{:x}", address)),
+      "value": field.value.as_ref().map(|value| value.to_json()),
+      "snapshot": field.snapshot.as_ref().map(|value| value.to_json()),
+      "attributes": dump_attributes(&field.attributes)
+    })
+  }).collect();
+
+  let properties: Vec<Value> = class.properties.iter().map(|property| {
+    json!({
+      "name": property.name,
+      "type": property.type_name.to_string(),
+      "get": dump_accessor(&property.getter),
+      "set": dump_accessor(&property.setter)
+    })
+  }).collect();
+
+  let events: Vec<Value> = class.events.iter().map(|event| {
+    json!({
+      "name": event.name,
+      "type": event.type_name.to_string(),
+      "add": dump_accessor(&event.add),
+      "remove": dump_accessor(&event.remove),
+      "raise": dump_accessor(&event.raise)
+    })
+  }).collect();
+
+  let methods: Vec<Value> = class.methods.iter().map(|method| {
+    let params: Vec<Value> = method.params.iter().map(|param| {
+      json!({
+        "name": param.name,
+        "type": param.type_name.to_string(),
+        "default": param.default.as_ref().map(|value| value.to_json()),
+        "attrs": param.attrs,
+        "decoded_flags": dump_flags(param.attrs, flags::PARAM_FLAGS),
+        "byref": param.byref,
+        "attributes": dump_attributes(&param.attributes)
+      })
+    }).collect();
+
+    json!({
+      "name": method.name,
+      "generic_params": dump_generic_params(&method.generic_params),
+      "flags": method.flags,
+      "decoded_flags": dump_flags(method.flags, flags::METHOD_FLAGS),
+      "rva": method.rva.map(|rva| format!("0x{:x}", rva)),
+      "code": dump_code(&method.code),
+      "internal_call": method.internal_call.as_ref().map(|call| json!({
+        "name": call.name,
+        "module": call.module,
+        "rva": call.rva.map(|rva| format!("0x{:x}", rva))
+      })),
+      "return_type": method.return_type.to_string(),
+      "return_byref": method.return_byref,
+      "params": params,
+      "attributes": dump_attributes(&method.attributes),
+      "generic_instances": dump_generic_instances(instances.get(&method.handle))
+    })
+  }).collect();
+
+  let vtable: Vec<Value> = class.vtable.iter().map(|slot| {
+    json!({
+      "slot": slot.slot,
+      "method": slot.method,
+      "rva": slot.rva.map(|rva| format!("0x{:x}", rva))
+    })
+  }).collect();
+
+  json!({
+    "namespace": class.namespace,
+    "name": class.name,
+    "full_name": class.full_name(),
+    "display_name": class.display_name(),
+    "generic_params": dump_generic_params(&class.generic_params),
+    "declaring_type": class.declaring_type,
+    "nested_types": class.nested_types.iter().map(|nested_type| nested_type.full_name()).collect::<Vec<_>>(),
+    "kind": class.kind.as_str(),
+    "flags": class.flags,
+    "decoded_flags": dump_flags(class.flags, flags::TYPE_FLAGS),
+    "layout": {
+      "instance_size": class.layout.instance_size,
+      "static_size": class.layout.static_size,
+      "element_size": class.layout.element_size,
+      "alignment": class.layout.alignment
+    },
+    "static_fields": class.static_fields.map(|address| format!("0x{:x}", address)),
+    "vtable": vtable,
+    "attributes": dump_attributes(&class.attributes),
+    "parent": class.parent.as_ref().map(TypeName::to_string),
+    "interfaces": class.interfaces.iter().map(TypeName::to_string).collect::<Vec<_>>(),
+    "fields": fields,
+    "properties": properties,
+    "events": events,
+    "methods": methods
+  })
+}
+
+fn header() -> Value {
+  json!({
+    "$schema": "types.schema.json",
+    "version": SCHEMA_VERSION,
+    "generator": format!("honkai-dumper {}", env!("CARGO_PKG_VERSION"))
+  })
+}
+
+// Types are written one at a time so neither the document nor an image ever has to be in memory at once
+pub fn dump(game: &Game, format: TypesFormat, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let file_name = compression.file_name(match format {
+    TypesFormat::Json => "types.json",
+    TypesFormat::JsonLines => "types.jsonl"
+  });
+
+  let mut sink = Sink::create(&file_name, compression)?;
+  let mut type_count = 0;
+
+  match format {
+    TypesFormat::Json => {
+      let mut output = JsonWriter::object(&mut sink)?;
+
+      for (key, value) in header().as_object().cloned().unwrap_or_default() {
+        output.entry(&key, &value)?;
+      }
+
+      let mut images = output.array_entry("images")?;
+
+      for ImageClasses { image, classes } in &game.images {
+        let mut entry = images.object_element()?;
+        entry.entry("index", &json!(image.index))?;
+        entry.entry("name", &json!(image.name))?;
+
+        let mut types = entry.array_entry("types")?;
+
+        for class in classes {
+          type_count += dump_classes(class, &game.instances, &mut |type_| types.element(&type_))?;
+        }
+
+        types.finish()?;
+        entry.finish()?;
+      }
+
+      images.finish()?;
+      output.finish()?;
+    },
+    TypesFormat::JsonLines => {
+      writeln!(sink, "{}", header())?;
+
+      for ImageClasses { image, classes } in &game.images {
+        let image = json!({ "index": image.index, "name": image.name });
+
+        // Every line is a type with the image it belongs to, so lines can be processed on their own
+        for class in classes {
+          type_count += dump_classes(class, &game.instances, &mut |mut type_| {
+            type_["image"] = image.clone();
+            writeln!(sink, "{}", type_)
+          })?;
+        }
+      }
+    }
+  }
+
+  sink.finish()?;
+
+  fs::write("types.schema.json", SCHEMA)?;
+
+  println!("{} types found and saved to {}", type_count, file_name);
+
+  Ok(())
+}
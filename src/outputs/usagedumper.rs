@@ -0,0 +1,25 @@
+use std::{collections::HashSet, error::Error};
+use serde_json::json;
+
+use crate::{model::usage::MetadataUsage, outputs::sink::{Compression, JsonWriter, Sink}};
+
+// Writes { "0x..": "TypeInfo: Ns.Class" } for every metadata usage slot the game has resolved
+pub fn dump(usages: &[MetadataUsage], compression: Compression) -> Result<(), Box<dyn Error>> {
+  let file_name = compression.file_name("metadatausages.json");
+  let mut sink = Sink::create(&file_name, compression)?;
+  let mut output = JsonWriter::object(&mut sink)?;
+
+  // Usages sharing a slot would give the same key twice
+  let mut written = HashSet::new();
+
+  for usage in usages.iter().filter(|usage| written.insert(usage.rva)) {
+    output.entry(&format!("0x{:x}", usage.rva), &json!(format!("{}: {}", usage.kind.as_str(), usage.kind.name())))?;
+  }
+
+  output.finish()?;
+  sink.finish()?;
+
+  println!("{} metadata usages found and saved to {}", usages.len(), file_name);
+
+  Ok(())
+}
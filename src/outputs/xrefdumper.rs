@@ -0,0 +1,179 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, error::Error, io::Write};
+use serde_json::json;
+
+use crate::{model::{class::Class, game::Game, type_name::TypeName}, outputs::sink::{Compression, JsonWriter, Sink}};
+
+// Everywhere a type is used, each entry is the full name of the member or type using it
+#[derive(Default)]
+struct Usages {
+  fields: BTreeSet<String>,
+  returned_by: BTreeSet<String>,
+  parameters: BTreeSet<String>,
+  subclasses: BTreeSet<String>,
+  implementers: BTreeSet<String>
+}
+
+impl Usages {
+  fn sections(&self) -> [(&'static str, &'static str, &BTreeSet<String>); 5] {
+    [
+      ("fields", "Fields", &self.fields),
+      ("returned_by", "Returned by", &self.returned_by),
+      ("parameters", "Parameters", &self.parameters),
+      ("subclasses", "Subclasses", &self.subclasses),
+      ("implementers", "Implementers", &self.implementers)
+    ]
+  }
+}
+
+// Keyed by (image, full name), full names aren't unique across images
+type Index = BTreeMap<(String, String), Usages>;
+
+// The images defining each full name, a referenced type is looked up in the image using it first and in every
+// image after that, the way the sql dump resolves its types
+struct Definitions<'a> {
+  by_name: HashMap<String, Vec<&'a str>>
+}
+
+impl<'a> Definitions<'a> {
+  fn collect(game: &'a Game) -> Definitions<'a> {
+    let mut by_name: HashMap<String, Vec<&str>> = HashMap::new();
+
+    for (image, class) in game.all_classes() {
+      by_name.entry(class.full_name()).or_default().push(&image.name);
+    }
+
+    Definitions { by_name }
+  }
+
+  // Types no image defines stay with the image using them
+  fn image_of(&self, user_image: &'a str, name: &str) -> &'a str {
+    match self.by_name.get(name) {
+      Some(images) if !images.contains(&user_image) => images[0],
+      _ => user_image
+    }
+  }
+}
+
+struct Indexer<'a> {
+  definitions: &'a Definitions<'a>,
+  image: &'a str,
+  index: &'a mut Index
+}
+
+impl Indexer<'_> {
+  // Keyed by the definition name, so List`1 and the List<int> a field uses end up together while Action and Action`1
+  // stay apart, the names of generic arguments and element types are their definition names as well
+  fn add(&mut self, type_name: &TypeName, section: fn(&mut Usages) -> &mut BTreeSet<String>, user: &str) {
+    for name in type_name.class_names() {
+      let key = (self.definitions.image_of(self.image, name).to_string(), name.to_string());
+      section(self.index.entry(key).or_default()).insert(user.to_string());
+    }
+  }
+
+  fn index_classes(&mut self, class: &Class) {
+    let class_name = class.full_name();
+
+    if let Some(parent) = &class.parent {
+      self.add(parent, |usages| &mut usages.subclasses, &class_name);
+    }
+
+    for interface in &class.interfaces {
+      self.add(interface, |usages| &mut usages.implementers, &class_name);
+    }
+
+    for field in &class.fields {
+      self.add(&field.type_name, |usages| &mut usages.fields, &format!("{}::{}", class_name, field.name));
+    }
+
+    for method in &class.methods {
+      // With the parameter types, so overloads stay apart
+      let signature = method.params.iter().map(|param| param.type_name.to_string()).collect::<Vec<_>>().join(", ");
+      let method_name = format!("{}::{}({})", class_name, method.name, signature);
+
+      self.add(&method.return_type, |usages| &mut usages.returned_by, &method_name);
+
+      for (i, param) in method.params.iter().enumerate() {
+        let param_name = if param.name.is_empty() { format!("param{}", i) } else { param.name.clone() };
+        self.add(&param.type_name, |usages| &mut usages.parameters, &format!("{} {}", method_name, param_name));
+      }
+    }
+
+    for nested_type in &class.nested_types {
+      self.index_classes(nested_type);
+    }
+  }
+}
+
+fn write_report(output: &mut impl Write, index: &Index) -> Result<(), Box<dyn Error>> {
+  for ((image, type_name), usages) in index {
+    writeln!(output, "{} [{}]", type_name, image)?;
+
+    for (_, title, users) in usages.sections() {
+      if users.is_empty() {
+        continue;
+      }
+
+      writeln!(output, "  {} ({})", title, users.len())?;
+
+      for user in users {
+        writeln!(output, "    {}", user)?;
+      }
+    }
+
+    writeln!(output)?;
+  }
+
+  Ok(())
+}
+
+// Saves where every type is used, as xrefs.json for tools and xrefs.txt to read or grep
+pub fn dump(game: &Game, compression: Compression) -> Result<(), Box<dyn Error>> {
+  let definitions = Definitions::collect(game);
+  let mut index = Index::new();
+
+  for entry in &game.images {
+    let mut indexer = Indexer { definitions: &definitions, image: &entry.image.name, index: &mut index };
+
+    for class in &entry.classes {
+      indexer.index_classes(class);
+    }
+  }
+
+  let json_name = compression.file_name("xrefs.json");
+  let mut sink = Sink::create(&json_name, compression)?;
+  let mut output = JsonWriter::object(&mut sink)?;
+  let mut images: Vec<&str> = index.keys().map(|(image, _)| image.as_str()).collect();
+  images.dedup();
+
+  // { image: { type: usages } }, the index is sorted by image so the types of every image are next to each other
+  for image in images {
+    let mut types = output.object_entry(image)?;
+
+    for ((_, type_name), usages) in index.range((image.to_string(), String::new())..).take_while(|((type_image, _), _)| type_image == image) {
+      let mut entry = json!({});
+
+      for (key, _, users) in usages.sections() {
+        if !users.is_empty() {
+          entry[key] = json!(users);
+        }
+      }
+
+      types.entry(type_name, &entry)?;
+    }
+
+    types.finish()?;
+  }
+
+  output.finish()?;
+  sink.finish()?;
+
+  let report_name = compression.file_name("xrefs.txt");
+  let mut sink = Sink::create(&report_name, compression)?;
+
+  write_report(&mut sink, &index)?;
+  sink.finish()?;
+
+  println!("{} referenced types saved to {} and {}", index.len(), json_name, report_name);
+
+  Ok(())
+}